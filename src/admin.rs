@@ -0,0 +1,183 @@
+use serde::Deserialize;
+use std::env;
+use std::sync::Arc;
+use warp::{Filter, Reply};
+
+use crate::error_log::ErrorBuffer;
+use crate::query::{InvalidQuery, QueryParams};
+use personal_api::models::{ErrorList, ErrorMessage};
+
+// Rejection for admin requests without a valid X-Admin-Token
+#[derive(Debug)]
+struct Unauthorized;
+
+impl warp::reject::Reject for Unauthorized {}
+
+// Admin token from ADMIN_API_TOKEN; admin routes refuse everything when unset
+pub fn token_from_env() -> Option<Arc<String>> {
+    match env::var("ADMIN_API_TOKEN") {
+        Ok(token) if !token.trim().is_empty() => Some(Arc::new(token.trim().to_string())),
+        _ => {
+            tracing::warn!("ADMIN_API_TOKEN not set, admin endpoints are disabled");
+            None
+        }
+    }
+}
+
+// Require X-Admin-Token to match the configured token
+pub fn require_token(
+    token: Option<Arc<String>>,
+) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::header::optional::<String>("x-admin-token")
+        .and_then(move |provided: Option<String>| {
+            let token = token.clone();
+            async move {
+                match (token, provided) {
+                    (Some(token), Some(provided)) if constant_time_eq(token.as_bytes(), provided.as_bytes()) => Ok(()),
+                    _ => Err(warp::reject::custom(Unauthorized)),
+                }
+            }
+        })
+        .untuple_one()
+}
+
+pub async fn handle_rejection(err: warp::Rejection) -> Result<warp::reply::Response, warp::Rejection> {
+    if err.find::<Unauthorized>().is_some() {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&ErrorMessage::new("unauthorized")),
+            warp::http::StatusCode::UNAUTHORIZED,
+        ).into_response());
+    }
+    if let Some(invalid) = err.find::<InvalidQuery>() {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&invalid.body()),
+            warp::http::StatusCode::BAD_REQUEST,
+        ).into_response());
+    }
+
+    Err(err)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ErrorsQuery {
+    route: Option<String>,
+}
+
+impl QueryParams for ErrorsQuery {
+    const PARAMS: &'static [(&'static str, &'static str)] = &[("route", "string")];
+}
+
+// GET and DELETE /api/admin/errors
+pub fn errors_route(
+    buffer: ErrorBuffer,
+    token: Option<Arc<String>>,
+) -> impl Filter<Extract = (warp::reply::Response,), Error = warp::Rejection> + Clone {
+    let with_buffer = warp::any().map(move || buffer.clone());
+    warp::path!("api" / "admin" / "errors")
+        .and(require_token(token))
+        .and(
+            warp::get()
+                .and(crate::query::strict::<ErrorsQuery>())
+                .and(with_buffer.clone())
+                .and_then(list_errors)
+                .map(Reply::into_response)
+                .or(warp::delete()
+                    .and(with_buffer)
+                    .and_then(clear_errors)
+                    .map(Reply::into_response))
+                .unify(),
+        )
+        .recover(handle_rejection)
+        .unify()
+}
+
+// GET /api/admin/errors - Recent error events, optionally for one route
+pub async fn list_errors(query: ErrorsQuery, buffer: ErrorBuffer) -> Result<impl Reply, warp::Rejection> {
+    let errors = buffer.entries(query.route.as_deref());
+    Ok(warp::reply::json(&ErrorList {
+        count: errors.len(),
+        errors,
+    }))
+}
+
+// DELETE /api/admin/errors - Clear the recent error buffer
+pub async fn clear_errors(buffer: ErrorBuffer) -> Result<impl Reply, warp::Rejection> {
+    buffer.clear();
+    Ok(warp::reply::with_status(warp::reply(), warp::http::StatusCode::NO_CONTENT))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use warp::http::StatusCode;
+
+    // A buffer holding one error event per (message, route) pair
+    fn buffer_with(entries: &[(&str, Option<&str>)]) -> ErrorBuffer {
+        use tracing_subscriber::prelude::*;
+
+        let buffer = ErrorBuffer::new(10);
+        let subscriber = tracing_subscriber::registry()
+            .with(buffer.layer().with_filter(tracing_subscriber::filter::filter_fn(crate::error_log::error_events)));
+        tracing::subscriber::with_default(subscriber, || {
+            for (message, route) in entries {
+                match route {
+                    Some(route) => tracing::info_span!("request", route).in_scope(|| tracing::error!("{}", message)),
+                    None => tracing::error!("{}", message),
+                }
+            }
+        });
+        buffer
+    }
+
+    async fn request(buffer: &ErrorBuffer, method: &str, path: &str, token: Option<&str>) -> (StatusCode, serde_json::Value) {
+        let route = errors_route(buffer.clone(), Some(Arc::new("secret".to_string())));
+        let mut request = warp::test::request().method(method).path(path);
+        if let Some(token) = token {
+            request = request.header("x-admin-token", token);
+        }
+        let response = request.reply(&route).await;
+        let body = serde_json::from_slice(response.body()).unwrap_or(serde_json::Value::Null);
+        (response.status(), body)
+    }
+
+    #[tokio::test]
+    async fn errors_can_be_filtered_by_route() {
+        let buffer = buffer_with(&[("a", Some("/api/contact")), ("b", Some("/api/resume")), ("c", None)]);
+
+        let (status, body) = request(&buffer, "GET", "/api/admin/errors", Some("secret")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["count"], 3);
+
+        let (_, body) = request(&buffer, "GET", "/api/admin/errors?route=/api/contact", Some("secret")).await;
+        assert_eq!(body["count"], 1);
+        assert_eq!(body["errors"][0]["message"], "a");
+
+        let (status, body) = request(&buffer, "GET", "/api/admin/errors?rout=/api/contact", Some("secret")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "unknown query parameter: rout");
+    }
+
+    #[tokio::test]
+    async fn delete_clears_the_buffer() {
+        let buffer = buffer_with(&[("a", None), ("b", None)]);
+
+        let (status, _) = request(&buffer, "DELETE", "/api/admin/errors", Some("secret")).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert!(buffer.entries(None).is_empty());
+        let (_, body) = request(&buffer, "GET", "/api/admin/errors", Some("secret")).await;
+        assert_eq!(body["count"], 0);
+    }
+
+    #[tokio::test]
+    async fn errors_need_the_admin_token() {
+        let buffer = buffer_with(&[("a", None)]);
+        assert_eq!(request(&buffer, "GET", "/api/admin/errors", None).await.0, StatusCode::UNAUTHORIZED);
+        assert_eq!(request(&buffer, "DELETE", "/api/admin/errors", Some("wrong")).await.0, StatusCode::UNAUTHORIZED);
+        assert_eq!(buffer.entries(None).len(), 1);
+    }
+}
@@ -0,0 +1,845 @@
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::env;
+use std::fmt::Write;
+use std::sync::{Arc, RwLock};
+
+use crate::outbound;
+use crate::routing::{Route, RoutingSchedule};
+use crate::translator::{Translation, Translator};
+use crate::{sanitize_input, Attribution, ContactForm};
+use personal_api::models::GeoLocation;
+
+const BREVO_API_URL: &str = "https://api.brevo.com/v3";
+
+// Brevo rejects sender names longer than this
+const MAX_SENDER_NAME_LEN: usize = 70;
+
+// Rough size of the fixed parts of the HTML template, used to pre-size buffers
+const HTML_TEMPLATE_LEN: usize = 512;
+
+#[derive(Debug, Serialize)]
+struct BrevoSender {
+    name: String,
+    email: String,
+}
+
+#[derive(Debug, Serialize)]
+struct BrevoRecipient {
+    email: String,
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BrevoSendersResponse {
+    senders: Vec<BrevoVerifiedSender>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BrevoVerifiedSender {
+    email: String,
+    active: Option<bool>,
+}
+
+enum SenderLookupError {
+    Unauthorized,
+    Other(anyhow::Error),
+}
+
+// Result of checking the configured sender against Brevo
+#[derive(Debug, Clone)]
+pub enum SenderStatus {
+    Unchecked,
+    Verified,
+    // The sender can't work; readiness fails with this message
+    Rejected(String),
+    // Brevo couldn't be asked; logged but not treated as fatal
+    Unreachable(String),
+}
+
+impl SenderStatus {
+    pub fn is_ready(&self) -> bool {
+        matches!(self, SenderStatus::Verified | SenderStatus::Unreachable(_))
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            SenderStatus::Unchecked => "sender verification pending".to_string(),
+            SenderStatus::Verified => "ok".to_string(),
+            SenderStatus::Rejected(reason) => reason.clone(),
+            SenderStatus::Unreachable(reason) => format!("ok (verification skipped: {})", reason),
+        }
+    }
+}
+
+// Sends contact notifications through Brevo. Everything that doesn't change
+// between messages is prepared once here instead of on every send.
+pub struct BrevoMailer {
+    client: Client,
+    api_key: String,
+    send_url: String,
+    senders_url: String,
+    display_timezone: chrono_tz::Tz,
+    translator: Option<Arc<dyn Translator>>,
+    sender: BrevoSender,
+    sender_status: RwLock<SenderStatus>,
+    // `{"sender":{...},"to":` serialized at startup
+    payload_prefix: Vec<u8>,
+    routing: RoutingSchedule,
+    // `[...]` recipient lists: default, one per routing rule, then vacation
+    default_to: Vec<u8>,
+    rule_to: Vec<Vec<u8>>,
+    vacation_to: Option<Vec<u8>>,
+    // `,"tags":[...]}` closing every payload, serialized at startup
+    payload_suffix: Vec<u8>,
+    // Domain used for the per-submitter References thread root
+    thread_domain: String,
+}
+
+// Settings for BrevoMailer, normally read from the environment
+pub struct BrevoConfig {
+    pub api_key: String,
+    pub sender_email: String,
+    pub sender_name: String,
+    pub recipient_email: String,
+    pub site_key: Option<String>,
+    pub display_timezone: chrono_tz::Tz,
+    // Base of the Brevo API, e.g. https://api.brevo.com/v3
+    pub api_url: String,
+}
+
+impl BrevoConfig {
+    pub fn from_env() -> Result<Self, anyhow::Error> {
+        let api_key = env::var("BREVO_API_KEY")
+            .map_err(|_| anyhow::anyhow!("BREVO_API_KEY environment variable not set"))?;
+
+        let sender_email = env::var("BREVO_SENDER_EMAIL")
+            .map_err(|_| anyhow::anyhow!("BREVO_SENDER_EMAIL environment variable not set"))?;
+
+        let sender_name = env::var("BREVO_SENDER_NAME")
+            .map_err(|_| anyhow::anyhow!("BREVO_SENDER_NAME environment variable not set"))?;
+
+        let recipient_email = env::var("CONTACT_RECIPIENT_EMAIL")
+            .unwrap_or_else(|_| sender_email.clone());
+
+        Ok(BrevoConfig {
+            api_key,
+            sender_email,
+            sender_name,
+            recipient_email,
+            site_key: env::var("SITE_KEY").ok(),
+            display_timezone: display_timezone_from_env(),
+            api_url: BREVO_API_URL.to_string(),
+        })
+    }
+}
+
+impl BrevoMailer {
+    pub fn from_env(
+        client: Client,
+        translator: Option<Arc<dyn Translator>>,
+        routing: RoutingSchedule,
+    ) -> Result<Self, anyhow::Error> {
+        Self::new(BrevoConfig::from_env()?, client, translator, routing)
+    }
+
+    pub fn new(
+        config: BrevoConfig,
+        client: Client,
+        translator: Option<Arc<dyn Translator>>,
+        routing: RoutingSchedule,
+    ) -> Result<Self, anyhow::Error> {
+        tracing::debug!(
+            "Using sender: {} <{}>, recipient: {}",
+            config.sender_name,
+            config.sender_email,
+            config.recipient_email
+        );
+
+        let sender = BrevoSender {
+            name: config.sender_name,
+            email: config.sender_email,
+        };
+        let mut payload_prefix = b"{\"sender\":".to_vec();
+        serde_json::to_writer(&mut payload_prefix, &sender)?;
+        payload_prefix.extend_from_slice(b",\"to\":");
+
+        let default_to = recipient_list(std::slice::from_ref(&config.recipient_email))?;
+        let rule_to = (0..routing.rule_count())
+            .map(|index| recipient_list(routing.recipients(Route::Rule(index)).unwrap_or_default()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let vacation_to = routing.recipients(Route::Vacation).map(recipient_list).transpose()?;
+
+        // Tags for filtering in the Brevo dashboard
+        let mut tags = vec!["contact-form".to_string()];
+        if let Some(site_key) = config.site_key.map(|v| sanitize_input(&v)).filter(|v| !v.is_empty()) {
+            tags.push(site_key);
+        }
+        let mut payload_suffix = b",\"tags\":".to_vec();
+        serde_json::to_writer(&mut payload_suffix, &tags)?;
+        payload_suffix.push(b'}');
+
+        let thread_domain = sender
+            .email
+            .rsplit_once('@')
+            .map(|(_, domain)| sanitize_input(domain))
+            .filter(|domain| !domain.is_empty())
+            .unwrap_or_else(|| "personal-api.local".to_string());
+
+        let api_url = config.api_url.trim_end_matches('/');
+        Ok(BrevoMailer {
+            client,
+            api_key: config.api_key,
+            send_url: format!("{}/smtp/email", api_url),
+            senders_url: format!("{}/senders", api_url),
+            display_timezone: config.display_timezone,
+            translator,
+            sender,
+            sender_status: RwLock::new(SenderStatus::Unchecked),
+            payload_prefix,
+            routing,
+            default_to,
+            rule_to,
+            vacation_to,
+            payload_suffix,
+            thread_domain,
+        })
+    }
+
+    pub fn sender_status(&self) -> SenderStatus {
+        self.sender_status.read().unwrap().clone()
+    }
+
+    // Check the sender name locally and the sender email against Brevo's
+    // verified senders, remembering the result for readiness
+    pub async fn verify_sender(&self) -> SenderStatus {
+        let status = match validate_sender_name(&self.sender.name) {
+            Err(reason) => SenderStatus::Rejected(reason),
+            Ok(()) => match self.fetch_verified_senders().await {
+                Ok(senders) => {
+                    let verified = senders.iter().any(|s| {
+                        s.email.eq_ignore_ascii_case(&self.sender.email) && s.active.unwrap_or(true)
+                    });
+                    if verified {
+                        SenderStatus::Verified
+                    } else {
+                        SenderStatus::Rejected(format!(
+                            "BREVO_SENDER_EMAIL {} is not a verified, active sender in Brevo",
+                            self.sender.email
+                        ))
+                    }
+                }
+                Err(SenderLookupError::Unauthorized) => {
+                    SenderStatus::Rejected("BREVO_API_KEY was rejected by Brevo".to_string())
+                }
+                Err(SenderLookupError::Other(e)) => SenderStatus::Unreachable(e.to_string()),
+            },
+        };
+
+        match &status {
+            SenderStatus::Verified => tracing::info!("Brevo sender {} is verified", self.sender.email),
+            SenderStatus::Rejected(reason) => tracing::error!("Brevo sender check failed: {}", reason),
+            SenderStatus::Unreachable(reason) => tracing::warn!("Could not verify Brevo sender: {}", reason),
+            SenderStatus::Unchecked => {}
+        }
+
+        *self.sender_status.write().unwrap() = status.clone();
+        status
+    }
+
+    async fn fetch_verified_senders(&self) -> Result<Vec<BrevoVerifiedSender>, SenderLookupError> {
+        let response = self
+            .client
+            .get(&self.senders_url)
+            .header("api-key", &self.api_key)
+            .header("Accept", "application/json")
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+            .await
+            .map_err(|e| SenderLookupError::Other(e.into()))?;
+
+        tracing::info!(
+            "Brevo senders API answered {} via {}",
+            response.status(),
+            outbound::describe_remote(response.remote_addr())
+        );
+
+        match response.status() {
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+                Err(SenderLookupError::Unauthorized)
+            }
+            status if !status.is_success() => Err(SenderLookupError::Other(anyhow::anyhow!(
+                "Brevo senders API returned {}",
+                status
+            ))),
+            _ => response
+                .json::<BrevoSendersResponse>()
+                .await
+                .map(|body| body.senders)
+                .map_err(|e| SenderLookupError::Other(e.into())),
+        }
+    }
+
+    // Send email via Brevo API
+    pub async fn send(
+        &self,
+        contact_form: &ContactForm,
+        contact_id: &str,
+        received_at: DateTime<Utc>,
+    ) -> Result<(), anyhow::Error> {
+        tracing::debug!("Attempting to send email via Brevo for contact ID: {}", contact_id);
+
+        // The original message is always included; the translation is best effort
+        let translation = match &self.translator {
+            Some(translator) => match translator.translate_to_english(&contact_form.message).await {
+                Ok(translation) if !translation.detected_language.starts_with("en") => {
+                    Some((translator.provider(), translation))
+                }
+                Ok(_) => None,
+                Err(e) => {
+                    tracing::warn!("Translation via {} failed for contact ID {}: {}", translator.provider(), contact_id, e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let route = self.routing.route(received_at);
+        tracing::info!("Contact ID {} routed by rule {}", contact_id, self.routing.label(route));
+
+        let payload = self.build_payload(contact_form, contact_id, received_at, route, translation.as_ref())?;
+
+        let response = self
+            .client
+            .post(&self.send_url)
+            .header("api-key", &self.api_key)
+            .header("Content-Type", "application/json")
+            .body(payload)
+            .send()
+            .await?;
+
+        let remote = outbound::describe_remote(response.remote_addr());
+
+        if response.status().is_success() {
+            tracing::info!("Email sent successfully via Brevo for contact ID: {} ({})", contact_id, remote);
+            Ok(())
+        } else {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            tracing::error!("Failed to send email via Brevo ({}): {}", remote, error_text);
+            Err(anyhow::anyhow!("Failed to send email: {}", error_text))
+        }
+    }
+
+    // Serialized Brevo payload: the cached prefix and recipients followed by
+    // subject and HTML
+    fn build_payload(
+        &self,
+        contact_form: &ContactForm,
+        contact_id: &str,
+        received_at: DateTime<Utc>,
+        route: Route,
+        translation: Option<&(&str, Translation)>,
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        let mut subject = String::with_capacity(
+            40 + contact_form.first_name.len() + contact_form.last_name.len(),
+        );
+        write!(
+            subject,
+            "New Contact Form Submission from {} {}",
+            contact_form.first_name, contact_form.last_name
+        )?;
+
+        let mut html = String::with_capacity(
+            HTML_TEMPLATE_LEN
+                + 2 * contact_form.message.len()
+                + translation.map(|(_, t)| 2 * t.text.len()).unwrap_or(0),
+        );
+        self.render_html(&mut html, contact_form, contact_id, received_at, translation)?;
+
+        let to = match route {
+            Route::Default => &self.default_to,
+            Route::Rule(index) => self.rule_to.get(index).unwrap_or(&self.default_to),
+            Route::Vacation => self.vacation_to.as_ref().unwrap_or(&self.default_to),
+        };
+
+        let mut payload = Vec::with_capacity(self.payload_prefix.len() + to.len() + subject.len() + html.len() + 80);
+        payload.extend_from_slice(&self.payload_prefix);
+        payload.extend_from_slice(to);
+        payload.extend_from_slice(b",\"subject\":");
+        serde_json::to_writer(&mut payload, &subject)?;
+        payload.extend_from_slice(b",\"htmlContent\":");
+        serde_json::to_writer(&mut payload, &html)?;
+        payload.extend_from_slice(b",\"headers\":");
+        serde_json::to_writer(&mut payload, &self.headers(contact_form, contact_id))?;
+        payload.extend_from_slice(&self.payload_suffix);
+
+        Ok(payload)
+    }
+
+    // Custom headers so messages from the same submitter thread together in
+    // a mail client. sanitize_input strips CR, LF and other controls, so no
+    // value can inject a header; the thread root is hex and the thread domain
+    // was sanitized at startup.
+    fn headers(&self, contact_form: &ContactForm, contact_id: &str) -> serde_json::Value {
+        let thread_root = format!("<{}@{}>", submitter_thread_id(&contact_form.email), self.thread_domain);
+
+        serde_json::json!({
+            "X-Contact-Id": sanitize_input(contact_id),
+            "In-Reply-To": thread_root,
+            "References": thread_root,
+        })
+    }
+
+    fn render_html(
+        &self,
+        out: &mut String,
+        contact_form: &ContactForm,
+        contact_id: &str,
+        received_at: DateTime<Utc>,
+        translation: Option<&(&str, Translation)>,
+    ) -> std::fmt::Result {
+        write!(
+            out,
+            r#"
+        <h2>New Contact Form Submission</h2>
+        <p><strong>Contact ID:</strong> {}</p>
+        <p><strong>Received:</strong> {}</p>
+        <p><strong>Name:</strong> "#,
+            contact_id,
+            received_at
+                .with_timezone(&self.display_timezone)
+                .format("%Y-%m-%d %H:%M:%S %Z"),
+        )?;
+        write_escaped_html(out, &contact_form.first_name);
+        out.push(' ');
+        write_escaped_html(out, &contact_form.last_name);
+        out.push_str(
+            r#"</p>
+        <p><strong>Email:</strong> "#,
+        );
+        write_escaped_html(out, &contact_form.email);
+        out.push_str(
+            r#"</p>
+        <p><strong>Phone:</strong> "#,
+        );
+        write_escaped_html(out, &contact_form.phone_number);
+        out.push_str(
+            r#"</p>
+        <p><strong>Message:</strong></p>
+        <p>"#,
+        );
+        write_escaped_with_line_breaks(out, &contact_form.message);
+        out.push_str("</p>");
+
+        if let Some((provider, translation)) = translation {
+            write!(
+                out,
+                r#"
+        <p><strong>Machine translation ({}, detected language: "#,
+                provider
+            )?;
+            write_escaped_html(out, &translation.detected_language);
+            out.push_str(
+                r#"):</strong></p>
+        <p>"#,
+            );
+            write_escaped_with_line_breaks(out, &translation.text);
+            out.push_str("</p>");
+        }
+
+        if let Some(summary) = contact_form
+            .attribution
+            .as_ref()
+            .map(Attribution::summary)
+            .filter(|summary| !summary.is_empty())
+        {
+            out.push_str(
+                r#"
+        <p><strong>Attribution:</strong> "#,
+            );
+            write_escaped_html(out, &summary);
+            out.push_str("</p>");
+        }
+
+        if let Some(location) = contact_form.location.as_ref().and_then(GeoLocation::summary) {
+            out.push_str(
+                r#"
+        <p><strong>Location (by IP):</strong> "#,
+            );
+            write_escaped_html(out, &location);
+            out.push_str("</p>");
+        }
+
+        if let Some(variant) = &contact_form.variant {
+            out.push_str(
+                r#"
+        <p><strong>Form variant:</strong> "#,
+            );
+            write_escaped_html(out, variant);
+            out.push_str("</p>");
+        }
+
+        out.push_str(
+            r#"
+        <hr>
+        <p><em>This message was sent from your website contact form.</em></p>
+        "#,
+        );
+
+        Ok(())
+    }
+}
+
+fn validate_sender_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("BREVO_SENDER_NAME is empty".to_string());
+    }
+    if name.chars().count() > MAX_SENDER_NAME_LEN {
+        return Err(format!("BREVO_SENDER_NAME is longer than {} characters", MAX_SENDER_NAME_LEN));
+    }
+    if name.chars().any(|c| c.is_control() || matches!(c, '<' | '>' | '"')) {
+        return Err("BREVO_SENDER_NAME contains control characters, quotes or angle brackets".to_string());
+    }
+    Ok(())
+}
+
+// Stable, non-reversible id for everyone submitting with the same email
+fn submitter_thread_id(email: &str) -> String {
+    let digest = format!("{:x}", Sha256::digest(email.trim().to_lowercase().as_bytes()));
+    format!("contact-thread-{}", &digest[..16])
+}
+
+// Serialized Brevo `to` list for the given addresses
+fn recipient_list(emails: &[String]) -> Result<Vec<u8>, serde_json::Error> {
+    let to: Vec<BrevoRecipient> = emails
+        .iter()
+        .map(|email| BrevoRecipient {
+            email: email.clone(),
+            name: Some("Contact Form".to_string()),
+        })
+        .collect();
+    serde_json::to_vec(&to)
+}
+
+// IANA timezone used for times shown in emails, from DISPLAY_TIMEZONE (default UTC)
+fn display_timezone_from_env() -> chrono_tz::Tz {
+    parse_display_timezone(&env::var("DISPLAY_TIMEZONE").unwrap_or_default())
+}
+
+fn parse_display_timezone(name: &str) -> chrono_tz::Tz {
+    match name.trim() {
+        "" => chrono_tz::UTC,
+        name => name.parse::<chrono_tz::Tz>().unwrap_or_else(|_| {
+            tracing::warn!("Unknown DISPLAY_TIMEZONE '{}', falling back to UTC", name);
+            chrono_tz::UTC
+        }),
+    }
+}
+
+// Append text escaped for HTML, with newlines turned into <br>, without an
+// intermediate String
+fn write_escaped_with_line_breaks(out: &mut String, text: &str) {
+    let mut lines = text.split('\n');
+    if let Some(first) = lines.next() {
+        write_escaped_html(out, first);
+    }
+    for line in lines {
+        out.push_str("<br>");
+        write_escaped_html(out, line);
+    }
+}
+
+// Append text escaped for inclusion in HTML email content
+fn write_escaped_html(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, brevo_config};
+    use async_trait::async_trait;
+    use chrono::TimeZone;
+    use warp::Filter;
+
+    struct FixedTranslator {
+        result: Result<(&'static str, &'static str), &'static str>,
+    }
+
+    #[async_trait]
+    impl Translator for FixedTranslator {
+        fn provider(&self) -> &str {
+            "Fixed"
+        }
+
+        async fn translate_to_english(&self, _text: &str) -> Result<Translation, anyhow::Error> {
+            match self.result {
+                Ok((detected_language, text)) => Ok(Translation {
+                    detected_language: detected_language.to_string(),
+                    text: text.to_string(),
+                }),
+                Err(e) => Err(anyhow::anyhow!(e)),
+            }
+        }
+    }
+
+    fn mailer(config: BrevoConfig, translator: Option<FixedTranslator>) -> BrevoMailer {
+        let translator = translator.map(|t| Arc::new(t) as Arc<dyn Translator>);
+        BrevoMailer::new(config, Client::new(), translator, RoutingSchedule::parse("", "", "").unwrap()).unwrap()
+    }
+
+    fn form(message: &str) -> ContactForm {
+        ContactForm {
+            email: "Jane@Example.com".to_string(),
+            first_name: "Jane".to_string(),
+            last_name: "Doe".to_string(),
+            phone_number: "+1 555 0100 200".to_string(),
+            message: message.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn received_at() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, 9, 30, 0).unwrap()
+    }
+
+    async fn sent_html(translator: Option<FixedTranslator>, message: &str) -> String {
+        let (url, payloads) = test_support::brevo_mock();
+        let form = ContactForm {
+            email: "Jane&co@Example.com".to_string(),
+            last_name: "<b>Doe</b> & Co".to_string(),
+            phone_number: "+1 555 <0100>".to_string(),
+            ..form(message)
+        };
+        mailer(brevo_config(&url), translator)
+            .send(&form, "contact-1", received_at())
+            .await
+            .unwrap();
+        let payload = payloads.lock().unwrap().pop().unwrap();
+        payload["htmlContent"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn english_messages_are_not_translated() {
+        let translator = FixedTranslator {
+            result: Ok(("en", "Hello there")),
+        };
+        let html = sent_html(Some(translator), "Hello there").await;
+        assert!(!html.contains("Machine translation"), "{}", html);
+    }
+
+    #[tokio::test]
+    async fn failed_translation_still_sends_the_original() {
+        let translator = FixedTranslator {
+            result: Err("translator down"),
+        };
+        let html = sent_html(Some(translator), "Hallo").await;
+        assert!(html.contains("<p>Hallo</p>"), "{}", html);
+        assert!(!html.contains("Machine translation"), "{}", html);
+    }
+
+    #[tokio::test]
+    async fn message_and_translation_are_escaped() {
+        let translator = FixedTranslator {
+            result: Ok(("<i>de</i>", "<b>Hello</b>\nthere & then")),
+        };
+        let html = sent_html(Some(translator), "<script>x</script>\nHallo").await;
+        assert!(html.contains("<p><strong>Name:</strong> Jane &lt;b&gt;Doe&lt;/b&gt; &amp; Co</p>"), "{}", html);
+        assert!(html.contains("<p><strong>Email:</strong> Jane&amp;co@Example.com</p>"), "{}", html);
+        assert!(html.contains("<p><strong>Phone:</strong> +1 555 &lt;0100&gt;</p>"), "{}", html);
+        assert!(html.contains("<p>&lt;script&gt;x&lt;/script&gt;<br>Hallo</p>"), "{}", html);
+        assert!(html.contains("Machine translation (Fixed, detected language: &lt;i&gt;de&lt;/i&gt;)"), "{}", html);
+        assert!(html.contains("<p>&lt;b&gt;Hello&lt;/b&gt;<br>there &amp; then</p>"), "{}", html);
+        assert!(!html.contains("<script>") && !html.contains("<b>") && !html.contains("<i>"), "{}", html);
+    }
+
+    fn received_line(mailer: &BrevoMailer, at: DateTime<Utc>) -> String {
+        let mut html = String::new();
+        mailer.render_html(&mut html, &form("Hello"), "contact-1", at, None).unwrap();
+        html.lines()
+            .find_map(|line| line.trim().strip_prefix("<p><strong>Received:</strong> "))
+            .and_then(|line| line.strip_suffix("</p>"))
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn received_time_follows_dst_in_the_display_timezone() {
+        let mailer = mailer(
+            BrevoConfig {
+                display_timezone: parse_display_timezone(" Europe/London "),
+                ..brevo_config("http://127.0.0.1:9")
+            },
+            None,
+        );
+        let at = |rfc3339: &str| DateTime::parse_from_rfc3339(rfc3339).unwrap().with_timezone(&Utc);
+
+        // Clocks go forward at 01:00 UTC on 29 March 2026
+        assert_eq!(received_line(&mailer, at("2026-03-29T00:59:59Z")), "2026-03-29 00:59:59 GMT");
+        assert_eq!(received_line(&mailer, at("2026-03-29T01:00:00Z")), "2026-03-29 02:00:00 BST");
+        // and back at 01:00 UTC on 25 October, so 01:30 local happens twice
+        assert_eq!(received_line(&mailer, at("2026-10-25T00:30:00Z")), "2026-10-25 01:30:00 BST");
+        assert_eq!(received_line(&mailer, at("2026-10-25T01:30:00Z")), "2026-10-25 01:30:00 GMT");
+    }
+
+    #[test]
+    fn display_timezone_defaults_to_utc() {
+        assert_eq!(parse_display_timezone(""), chrono_tz::UTC);
+        assert_eq!(parse_display_timezone("Mars/Olympus"), chrono_tz::UTC);
+        assert_eq!(parse_display_timezone("America/New_York"), chrono_tz::America::New_York);
+
+        let mailer = test_support::brevo_mailer("http://127.0.0.1:9");
+        assert_eq!(received_line(&mailer, received_at()), "2026-03-02 09:30:00 UTC");
+    }
+
+    #[test]
+    fn payload_matches_golden_snapshot() {
+        let mailer = mailer(
+            BrevoConfig {
+                site_key: Some("personal-site\r\nBcc: someone@example.com".to_string()),
+                display_timezone: chrono_tz::Europe::London,
+                ..brevo_config("http://127.0.0.1:9")
+            },
+            None,
+        );
+        let form = ContactForm {
+            attribution: Some(Attribution {
+                utm_source: Some("linkedin".to_string()),
+                landing_page: Some("/".to_string()),
+                ..Default::default()
+            }),
+            variant: Some("short".to_string()),
+            location: Some(GeoLocation {
+                country_code: Some("DE".to_string()),
+                city: Some("Berlin".to_string()),
+            }),
+            last_name: "<Doe> & Co".to_string(),
+            ..form("Hallo,\nich habe eine Frage.")
+        };
+        let translation = (
+            "LibreTranslate",
+            Translation {
+                detected_language: "de".to_string(),
+                text: "Hello,\nI have a question.".to_string(),
+            },
+        );
+
+        let payload = mailer
+            .build_payload(&form, "contact-1", received_at(), Route::Default, Some(&translation))
+            .unwrap();
+        let actual: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        let expected: serde_json::Value =
+            serde_json::from_str(include_str!("../testdata/brevo_payload.json")).unwrap();
+        assert_eq!(actual, expected, "{}", serde_json::to_string_pretty(&actual).unwrap());
+    }
+
+    // Mock Brevo senders endpoint answering every lookup with `status` and `body`
+    fn senders_mock(status: u16, body: serde_json::Value) -> String {
+        test_support::serve(
+            warp::path!("senders")
+                .and(warp::get())
+                .and(warp::header::exact("api-key", "brevo-key"))
+                .map(move || {
+                    warp::reply::with_status(
+                        warp::reply::json(&body),
+                        warp::http::StatusCode::from_u16(status).unwrap(),
+                    )
+                }),
+        )
+    }
+
+    async fn sender_status(api_url: &str) -> SenderStatus {
+        let mailer = test_support::brevo_mailer(api_url);
+        let status = mailer.verify_sender().await;
+        assert_eq!(mailer.sender_status().describe(), status.describe());
+        status
+    }
+
+    #[tokio::test]
+    async fn verified_sender_is_ready() {
+        let url = senders_mock(
+            200,
+            serde_json::json!({ "senders": [
+                { "email": "other@example.com", "active": true },
+                { "email": "SITE@example.com", "active": true },
+            ] }),
+        );
+        let status = sender_status(&url).await;
+        assert!(matches!(status, SenderStatus::Verified), "{:?}", status);
+        assert!(status.is_ready());
+    }
+
+    #[tokio::test]
+    async fn unverified_or_inactive_sender_blocks_readiness() {
+        let url = senders_mock(200, serde_json::json!({ "senders": [{ "email": "other@example.com" }] }));
+        let status = sender_status(&url).await;
+        assert!(matches!(status, SenderStatus::Rejected(_)), "{:?}", status);
+        assert!(!status.is_ready());
+
+        let url = senders_mock(200, serde_json::json!({ "senders": [{ "email": "site@example.com", "active": false }] }));
+        assert!(!sender_status(&url).await.is_ready());
+
+        let url = senders_mock(401, serde_json::json!({ "code": "unauthorized" }));
+        let status = sender_status(&url).await;
+        assert_eq!(status.describe(), "BREVO_API_KEY was rejected by Brevo");
+        assert!(!status.is_ready());
+    }
+
+    #[tokio::test]
+    async fn unreachable_brevo_warns_without_blocking() {
+        // Nothing listens on the discard port
+        let status = sender_status("http://127.0.0.1:9").await;
+        assert!(matches!(status, SenderStatus::Unreachable(_)), "{:?}", status);
+        assert!(status.is_ready());
+        assert!(status.describe().starts_with("ok (verification skipped: "));
+
+        let url = senders_mock(503, serde_json::json!({}));
+        let status = sender_status(&url).await;
+        assert!(matches!(status, SenderStatus::Unreachable(_)), "{:?}", status);
+        assert!(status.is_ready());
+    }
+
+    #[tokio::test]
+    async fn headers_and_tags_serialize_as_brevo_expects() {
+        let (url, payloads) = test_support::brevo_mock();
+        let with_site_key = mailer(BrevoConfig { site_key: Some("personal-site".to_string()), ..brevo_config(&url) }, None);
+        with_site_key.send(&form("Hello"), "contact-1", received_at()).await.unwrap();
+        test_support::brevo_mailer(&url).send(&form("Hello"), "contact-2", received_at()).await.unwrap();
+
+        let payloads = payloads.lock().unwrap();
+        let headers = payloads[0]["headers"].as_object().unwrap();
+        assert_eq!(headers.len(), 3);
+        assert_eq!(headers["X-Contact-Id"], "contact-1");
+        assert_eq!(headers["In-Reply-To"], headers["References"]);
+        assert!(headers["References"].as_str().unwrap().ends_with("@example.com>"));
+        assert_eq!(payloads[0]["tags"], serde_json::json!(["contact-form", "personal-site"]));
+        assert_eq!(payloads[1]["tags"], serde_json::json!(["contact-form"]));
+    }
+
+    #[tokio::test]
+    async fn submissions_from_one_email_share_a_thread_root() {
+        let (url, payloads) = test_support::brevo_mock();
+        let mailer = test_support::brevo_mailer(&url);
+        let again = ContactForm { email: "jane@example.COM".to_string(), ..form("Second message") };
+        let other = ContactForm { email: "john@example.com".to_string(), ..form("Hello") };
+        mailer.send(&form("Hello"), "contact-1", received_at()).await.unwrap();
+        mailer.send(&again, "contact-2", received_at()).await.unwrap();
+        mailer.send(&other, "contact-3", received_at()).await.unwrap();
+
+        let payloads = payloads.lock().unwrap();
+        let root = |i: usize| payloads[i]["headers"]["References"].as_str().unwrap().to_string();
+        assert_eq!(root(0), root(1));
+        assert_ne!(root(0), root(2));
+        assert_ne!(payloads[0]["headers"]["X-Contact-Id"], payloads[1]["headers"]["X-Contact-Id"]);
+    }
+}
@@ -0,0 +1,156 @@
+use std::env;
+use std::sync::Arc;
+use warp::http::header::{HeaderValue, CACHE_CONTROL};
+use warp::Filter;
+
+// Cache-Control policy per route: path pattern -> header value.
+// Patterns ending in '*' match by prefix, everything else matches exactly.
+pub struct CacheControlPolicy {
+    rules: Vec<(String, String)>,
+}
+
+impl CacheControlPolicy {
+    // Format: CACHE_CONTROL_POLICIES="/api/resume=public, max-age=600;/health=no-cache"
+    pub fn from_env() -> Self {
+        Self::parse(&env::var("CACHE_CONTROL_POLICIES").unwrap_or_default())
+    }
+
+    pub fn parse(config: &str) -> Self {
+        let mut rules = Vec::new();
+
+        // Overrides come first so they win over the defaults below
+        for entry in config.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+            match entry.split_once('=') {
+                Some((pattern, value)) if !value.trim().is_empty() && HeaderValue::from_str(value.trim()).is_ok() => {
+                    rules.push((pattern.trim().to_string(), value.trim().to_string()));
+                }
+                _ => tracing::warn!("Ignoring invalid CACHE_CONTROL_POLICIES entry: {}", entry),
+            }
+        }
+
+        rules.push(("/health".to_string(), "no-store".to_string()));
+        rules.push(("/ready".to_string(), "no-store".to_string()));
+        rules.push(("/api/contact*".to_string(), "no-store".to_string()));
+        rules.push(("/api/resume*".to_string(), "public, max-age=86400".to_string()));
+        rules.push(("/api/stats*".to_string(), "public, max-age=3600".to_string()));
+        rules.push(("/api/deprecations".to_string(), "public, max-age=3600".to_string()));
+        rules.push(("/api/admin*".to_string(), "no-store".to_string()));
+
+        CacheControlPolicy { rules }
+    }
+
+    pub fn header_for(&self, path: &str) -> String {
+        self.rules
+            .iter()
+            .find(|(pattern, _)| match pattern.strip_suffix('*') {
+                Some(prefix) => path.starts_with(prefix),
+                None => path == pattern,
+            })
+            .map(|(_, value)| value.clone())
+            .unwrap_or_else(|| "no-cache".to_string())
+    }
+}
+
+// Adds Cache-Control to every response from `routes`, looked up by the
+// request path without BASE_PATH. Error responses are never cached, and a
+// header the handler set itself is left alone.
+pub fn apply<F>(
+    policy: Arc<CacheControlPolicy>,
+    base_path: String,
+    routes: F,
+) -> impl Filter<Extract = (warp::reply::Response,), Error = warp::Rejection> + Clone
+where
+    F: Filter<Extract = (warp::reply::Response,), Error = warp::Rejection> + Clone + Send + Sync + 'static,
+{
+    warp::path::full()
+        .and(routes)
+        .map(move |path: warp::path::FullPath, mut response: warp::reply::Response| {
+            if !response.headers().contains_key(CACHE_CONTROL) {
+                let value = if response.status().is_client_error() || response.status().is_server_error() {
+                    "no-store".to_string()
+                } else {
+                    let route = path.as_str().strip_prefix(base_path.as_str()).unwrap_or(path.as_str());
+                    policy.header_for(route)
+                };
+                if let Ok(value) = HeaderValue::from_str(&value) {
+                    response.headers_mut().insert(CACHE_CONTROL, value);
+                }
+            }
+            response
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use warp::http::StatusCode;
+    use warp::Reply;
+
+    // /api/resume/* answers 200, /api/resume/missing 404 and /api/resume/own
+    // sets its own header; everything else is 200
+    fn routes() -> impl Filter<Extract = (warp::reply::Response,), Error = warp::Rejection> + Clone {
+        warp::path!("api" / "resume" / "missing")
+            .map(|| warp::reply::with_status("gone", StatusCode::NOT_FOUND).into_response())
+            .or(warp::path!("api" / "resume" / "own")
+                .map(|| warp::reply::with_header("x", "cache-control", "private").into_response()))
+            .unify()
+            .or(warp::get().map(|| warp::reply().into_response()))
+            .unify()
+    }
+
+    async fn header(policy: &str, base_path: &str, path: &str) -> (StatusCode, String) {
+        let filter = apply(Arc::new(CacheControlPolicy::parse(policy)), base_path.to_string(), routes());
+        let response = warp::test::request().path(path).reply(&filter).await;
+        let value = response.headers()[CACHE_CONTROL].to_str().unwrap().to_string();
+        (response.status(), value)
+    }
+
+    #[test]
+    fn defaults_by_route() {
+        let policy = CacheControlPolicy::parse("");
+        assert_eq!(policy.header_for("/health"), "no-store");
+        assert_eq!(policy.header_for("/api/contact"), "no-store");
+        assert_eq!(policy.header_for("/api/resume"), "public, max-age=86400");
+        assert_eq!(policy.header_for("/api/resume/text"), "public, max-age=86400");
+        assert_eq!(policy.header_for("/api/stats/public"), "public, max-age=3600");
+        assert_eq!(policy.header_for("/api/deprecations"), "public, max-age=3600");
+        assert_eq!(policy.header_for("/api/admin/errors"), "no-store");
+        assert_eq!(policy.header_for("/somewhere/else"), "no-cache");
+    }
+
+    #[test]
+    fn overrides_win_over_defaults() {
+        let policy = CacheControlPolicy::parse("/api/resume/text=no-cache; /api/admin*=private;broken;/bad=");
+        assert_eq!(policy.header_for("/api/resume/text"), "no-cache");
+        assert_eq!(policy.header_for("/api/resume/meta"), "public, max-age=86400");
+        assert_eq!(policy.header_for("/api/admin/errors"), "private");
+        assert_eq!(policy.header_for("/bad"), "no-cache");
+    }
+
+    #[tokio::test]
+    async fn policy_follows_the_request_path() {
+        let policy = "/api/resume/text=no-cache";
+        assert_eq!(header(policy, "", "/api/resume/text").await.1, "no-cache");
+        assert_eq!(header(policy, "", "/api/resume/meta").await.1, "public, max-age=86400");
+        assert_eq!(header(policy, "", "/health").await.1, "no-store");
+    }
+
+    #[tokio::test]
+    async fn policy_ignores_base_path() {
+        assert_eq!(header("", "/svc", "/svc/api/resume").await.1, "public, max-age=86400");
+        assert_eq!(header("", "/svc", "/svc/health").await.1, "no-store");
+    }
+
+    #[tokio::test]
+    async fn errors_are_never_cached() {
+        assert_eq!(
+            header("", "", "/api/resume/missing").await,
+            (StatusCode::NOT_FOUND, "no-store".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn handler_header_is_kept() {
+        assert_eq!(header("", "", "/api/resume/own").await.1, "private");
+    }
+}
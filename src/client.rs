@@ -0,0 +1,206 @@
+use reqwest::{Client, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use std::fmt;
+
+use crate::models::{
+    ContactError, ContactForm, ContactResponseV2, ErrorList, ErrorMessage, ReadyStatus, RetrySummary, Submission,
+    SubmissionList, SubmissionStatus, CONTACT_V2_MEDIA_TYPE,
+};
+
+#[derive(Debug)]
+pub enum ClientError {
+    // The request could not be sent or the response could not be read
+    Http(reqwest::Error),
+    // The API answered with an error status
+    Api {
+        status: u16,
+        message: String,
+        details: Option<serde_json::Value>,
+    },
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Http(e) => write!(f, "request failed: {}", e),
+            ClientError::Api { status, message, .. } => write!(f, "API returned {}: {}", status, message),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ClientError::Http(e) => Some(e),
+            ClientError::Api { .. } => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(e: reqwest::Error) -> Self {
+        ClientError::Http(e)
+    }
+}
+
+// Typed client for this API. `base_url` includes any BASE_PATH, e.g.
+// "https://michaelhenry.me/api-service". Admin calls need the admin token and,
+// unless the admin listener is shared, a client pointed at the admin address.
+#[derive(Debug, Clone)]
+pub struct PersonalApiClient {
+    http: Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl PersonalApiClient {
+    pub fn new(base_url: impl Into<String>, token: Option<String>) -> Self {
+        PersonalApiClient {
+            http: Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            token,
+        }
+    }
+
+    // Use an existing reqwest client, e.g. one with custom timeouts
+    pub fn with_http_client(mut self, http: Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    fn admin(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.token {
+            Some(token) => request.header("X-Admin-Token", token),
+            None => request,
+        }
+    }
+
+    // POST /api/v2/contact. A submission that was stored but whose email
+    // failed is still Ok, with `delivery` set to failed; the server resends it.
+    pub async fn submit_contact(&self, form: &ContactForm) -> Result<ContactResponseV2, ClientError> {
+        let response = self
+            .http
+            .post(self.url("/api/v2/contact"))
+            .header("Accept", CONTACT_V2_MEDIA_TYPE)
+            .json(form)
+            .send()
+            .await?;
+
+        decode(response, &[]).await
+    }
+
+    // GET /ready. An unavailable service is still Ok, with the reason in `checks`.
+    pub async fn get_status(&self) -> Result<ReadyStatus, ClientError> {
+        let response = self.http.get(self.url("/ready")).send().await?;
+        decode(response, &[StatusCode::SERVICE_UNAVAILABLE]).await
+    }
+
+    // GET /api/admin/errors, optionally for one route
+    pub async fn list_errors(&self, route: Option<&str>) -> Result<ErrorList, ClientError> {
+        let mut request = self.http.get(self.url("/api/admin/errors"));
+        if let Some(route) = route {
+            request = request.query(&[("route", route)]);
+        }
+        let response = self.admin(request).send().await?;
+        decode(response, &[]).await
+    }
+
+    // GET /api/contact/submissions, newest first, optionally one status
+    pub async fn list_submissions(&self, status: Option<SubmissionStatus>) -> Result<SubmissionList, ClientError> {
+        let mut request = self.http.get(self.url("/api/contact/submissions"));
+        if let Some(status) = status {
+            request = request.query(&[("status", status)]);
+        }
+        let response = self.admin(request).send().await?;
+        decode(response, &[]).await
+    }
+
+    // GET /api/contact/submissions/{id}
+    pub async fn get_submission(&self, id: &str) -> Result<Submission, ClientError> {
+        let url = self.url(&format!("/api/contact/submissions/{}", encode_path_segment(id)));
+        let response = self.admin(self.http.get(url)).send().await?;
+        decode(response, &[]).await
+    }
+
+    // POST /api/contact/retry-failed
+    pub async fn retry_failed(&self) -> Result<RetrySummary, ClientError> {
+        let response = self.admin(self.http.post(self.url("/api/contact/retry-failed"))).send().await?;
+        decode(response, &[]).await
+    }
+
+    // DELETE /api/admin/errors
+    pub async fn clear_errors(&self) -> Result<(), ClientError> {
+        let response = self.admin(self.http.delete(self.url("/api/admin/errors"))).send().await?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(api_error(response).await)
+        }
+    }
+}
+
+// Decode a success body, also accepting the listed error statuses whose
+// body has the same shape
+async fn decode<T: DeserializeOwned>(response: Response, also_ok: &[StatusCode]) -> Result<T, ClientError> {
+    if response.status().is_success() || also_ok.contains(&response.status()) {
+        Ok(response.json().await?)
+    } else {
+        Err(api_error(response).await)
+    }
+}
+
+async fn api_error(response: Response) -> ClientError {
+    let status = response.status().as_u16();
+    let body = match response.text().await {
+        Ok(body) => body,
+        Err(e) => return ClientError::Http(e),
+    };
+
+    if let Ok(error) = serde_json::from_str::<ContactError>(&body) {
+        return ClientError::Api {
+            status,
+            message: error.message,
+            details: error.errors,
+        };
+    }
+    match serde_json::from_str::<ErrorMessage>(&body) {
+        Ok(error) => ClientError::Api {
+            status,
+            message: error.error,
+            details: (!error.details.is_empty()).then_some(serde_json::Value::Object(error.details)),
+        },
+        Err(_) => ClientError::Api {
+            status,
+            message: body,
+            details: None,
+        },
+    }
+}
+
+// Percent-encode everything but unreserved characters, so an id can't add
+// path segments or a query string
+fn encode_path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_stay_in_one_path_segment() {
+        assert_eq!(encode_path_segment("3f2c-41aa_b.~"), "3f2c-41aa_b.~");
+        assert_eq!(encode_path_segment("../errors?x=1#y"), "..%2Ferrors%3Fx%3D1%23y");
+        assert_eq!(encode_path_segment("a b/é"), "a%20b%2F%C3%A9");
+    }
+}
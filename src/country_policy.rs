@@ -0,0 +1,141 @@
+use serde::Serialize;
+use std::env;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CountryAction {
+    Allow,
+    Block,
+}
+
+struct CountryRule {
+    // ISO country code, or "*" for the default
+    country: String,
+    action: CountryAction,
+    hits: AtomicU64,
+}
+
+// What to do with a submission by the submitter's country, from COUNTRY_POLICY.
+// Submissions with no known country (private IPs, no GeoIP database) and
+// countries without a rule get the "*" rule, or are allowed when there is none.
+pub struct CountryPolicy {
+    rules: Vec<CountryRule>,
+}
+
+impl CountryPolicy {
+    // Format: COUNTRY_POLICY="CN=block,*=allow"
+    pub fn from_env() -> Self {
+        Self::parse(&env::var("COUNTRY_POLICY").unwrap_or_default())
+    }
+
+    pub fn parse(config: &str) -> Self {
+        let mut rules: Vec<CountryRule> = Vec::new();
+
+        for entry in config.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let parsed = entry.split_once('=').and_then(|(country, action)| {
+                let country = country.trim().to_uppercase();
+                let valid_country = country == "*" || (country.len() == 2 && country.chars().all(|c| c.is_ascii_alphabetic()));
+                let action = match action.trim().to_lowercase().as_str() {
+                    "allow" => CountryAction::Allow,
+                    "block" => CountryAction::Block,
+                    _ => return None,
+                };
+                valid_country.then_some((country, action))
+            });
+
+            match parsed {
+                Some((country, _)) if rules.iter().any(|rule| rule.country == country) => {
+                    tracing::warn!("Ignoring duplicate COUNTRY_POLICY entry: {}", entry);
+                }
+                Some((country, action)) => rules.push(CountryRule {
+                    country,
+                    action,
+                    hits: AtomicU64::new(0),
+                }),
+                None => tracing::warn!("Ignoring invalid COUNTRY_POLICY entry (actions are allow or block): {}", entry),
+            }
+        }
+
+        CountryPolicy { rules }
+    }
+
+    // The action for a country code, counting a hit on the rule that matched
+    pub fn evaluate(&self, country_code: Option<&str>) -> CountryAction {
+        let rule = country_code
+            .and_then(|code| self.rules.iter().find(|rule| rule.country.eq_ignore_ascii_case(code)))
+            .or_else(|| self.rules.iter().find(|rule| rule.country == "*"));
+
+        match rule {
+            Some(rule) => {
+                rule.hits.fetch_add(1, Ordering::Relaxed);
+                rule.action
+            }
+            None => CountryAction::Allow,
+        }
+    }
+
+    // Hits per rule, for the admin endpoint
+    pub fn hits(&self) -> serde_json::Value {
+        self.rules
+            .iter()
+            .map(|rule| {
+                serde_json::json!({
+                    "country": rule.country,
+                    "action": rule.action,
+                    "hits": rule.hits.load(Ordering::Relaxed),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn country_rules_win_over_the_default() {
+        let policy = CountryPolicy::parse("cn=block, *=allow");
+        assert_eq!(policy.evaluate(Some("CN")), CountryAction::Block);
+        assert_eq!(policy.evaluate(Some("cn")), CountryAction::Block);
+        assert_eq!(policy.evaluate(Some("GB")), CountryAction::Allow);
+
+        let policy = CountryPolicy::parse("GB=allow,*=block");
+        assert_eq!(policy.evaluate(Some("GB")), CountryAction::Allow);
+        assert_eq!(policy.evaluate(Some("US")), CountryAction::Block);
+    }
+
+    #[test]
+    fn unknown_countries_get_the_default() {
+        assert_eq!(CountryPolicy::parse("*=block").evaluate(None), CountryAction::Block);
+        assert_eq!(CountryPolicy::parse("CN=block").evaluate(None), CountryAction::Allow);
+        assert_eq!(CountryPolicy::parse("").evaluate(Some("CN")), CountryAction::Allow);
+    }
+
+    #[test]
+    fn invalid_and_duplicate_entries_are_ignored() {
+        let policy = CountryPolicy::parse("CN=quarantine,RU=captcha,USA=block,FR,DE=block,de=allow");
+        assert_eq!(policy.evaluate(Some("CN")), CountryAction::Allow);
+        assert_eq!(policy.evaluate(Some("DE")), CountryAction::Block);
+
+        let countries: Vec<_> = policy.hits().as_array().unwrap().iter().map(|rule| rule["country"].clone()).collect();
+        assert_eq!(countries, ["DE"]);
+    }
+
+    #[test]
+    fn hits_are_counted_per_rule() {
+        let policy = CountryPolicy::parse("CN=block,*=allow");
+        policy.evaluate(Some("CN"));
+        policy.evaluate(Some("GB"));
+        policy.evaluate(None);
+
+        assert_eq!(
+            policy.hits(),
+            serde_json::json!([
+                { "country": "CN", "action": "block", "hits": 1 },
+                { "country": "*", "action": "allow", "hits": 2 },
+            ])
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use warp::http::HeaderValue;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeprecationKind {
+    Field,
+}
+
+// Something clients should stop using. Sunset is a YYYY-MM-DD date, or None
+// until a removal date has been decided.
+#[derive(Debug, Serialize)]
+pub struct Deprecation {
+    pub item: &'static str,
+    pub kind: DeprecationKind,
+    pub replacement: &'static str,
+    pub sunset: Option<&'static str>,
+}
+
+// Every deprecation lives here; routes refer to entries by item
+pub const REGISTRY: &[Deprecation] = &[
+    Deprecation {
+        item: "contact.first_name",
+        kind: DeprecationKind::Field,
+        replacement: "contact.firstName",
+        sunset: None,
+    },
+    Deprecation {
+        item: "contact.last_name",
+        kind: DeprecationKind::Field,
+        replacement: "contact.lastName",
+        sunset: None,
+    },
+    Deprecation {
+        item: "contact.phone_number",
+        kind: DeprecationKind::Field,
+        replacement: "contact.phoneNumber",
+        sunset: None,
+    },
+    Deprecation {
+        item: "contact.attribution.utm_source",
+        kind: DeprecationKind::Field,
+        replacement: "contact.attribution.utmSource",
+        sunset: None,
+    },
+    Deprecation {
+        item: "contact.attribution.utm_medium",
+        kind: DeprecationKind::Field,
+        replacement: "contact.attribution.utmMedium",
+        sunset: None,
+    },
+    Deprecation {
+        item: "contact.attribution.utm_campaign",
+        kind: DeprecationKind::Field,
+        replacement: "contact.attribution.utmCampaign",
+        sunset: None,
+    },
+];
+
+static USAGE: [AtomicU64; REGISTRY.len()] = [const { AtomicU64::new(0) }; REGISTRY.len()];
+
+// Deprecated contact form field names, from the "contact." entries
+pub fn contact_fields() -> impl Iterator<Item = &'static str> {
+    REGISTRY.iter().filter_map(|deprecation| deprecation.item.strip_prefix("contact."))
+}
+
+// Count uses of the given items and add Deprecation/Sunset headers to the
+// reply. The earliest sunset wins when several items are used at once.
+pub fn annotate(reply: &mut warp::reply::Response, items: &[impl AsRef<str>]) {
+    annotate_from(REGISTRY, &USAGE, reply, items);
+}
+
+fn annotate_from(
+    registry: &[Deprecation],
+    usage: &[AtomicU64],
+    reply: &mut warp::reply::Response,
+    items: &[impl AsRef<str>],
+) {
+    let mut sunset: Option<NaiveDate> = None;
+    let mut used = false;
+
+    for item in items.iter().map(AsRef::as_ref) {
+        let Some(index) = registry.iter().position(|deprecation| deprecation.item == item) else {
+            tracing::warn!("Unknown deprecation item {}", item);
+            continue;
+        };
+        usage[index].fetch_add(1, Ordering::Relaxed);
+        used = true;
+
+        if let Some(date) = registry[index].sunset.and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()) {
+            sunset = Some(sunset.map_or(date, |current| current.min(date)));
+        }
+    }
+
+    if !used {
+        return;
+    }
+
+    let headers = reply.headers_mut();
+    headers.insert("Deprecation", HeaderValue::from_static("true"));
+    if let Some(date) = sunset {
+        let http_date = date.format("%a, %d %b %Y 00:00:00 GMT").to_string();
+        if let Ok(value) = HeaderValue::from_str(&http_date) {
+            headers.insert("Sunset", value);
+        }
+    }
+}
+
+// GET /api/deprecations - The registry, for client authors
+pub fn listing() -> serde_json::Value {
+    serde_json::json!({ "deprecations": REGISTRY })
+}
+
+// Per-item usage since startup, for deciding when something can go
+pub fn usage() -> serde_json::Value {
+    REGISTRY
+        .iter()
+        .zip(USAGE.iter())
+        .map(|(deprecation, count)| {
+            serde_json::json!({
+                "item": deprecation.item,
+                "kind": deprecation.kind,
+                "sunset": deprecation.sunset,
+                "uses": count.load(Ordering::Relaxed),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use warp::Reply;
+
+    const TEST_REGISTRY: &[Deprecation] = &[
+        Deprecation { item: "a", kind: DeprecationKind::Field, replacement: "a2", sunset: None },
+        Deprecation { item: "b", kind: DeprecationKind::Field, replacement: "b2", sunset: Some("2027-06-30") },
+        Deprecation { item: "c", kind: DeprecationKind::Field, replacement: "c2", sunset: Some("2027-01-15") },
+    ];
+
+    // (Deprecation, Sunset) headers after annotating a reply for `items`
+    fn headers(usage: &[AtomicU64], items: &[&str]) -> (Option<String>, Option<String>) {
+        let mut reply = warp::reply().into_response();
+        annotate_from(TEST_REGISTRY, usage, &mut reply, items);
+        let header = |name| reply.headers().get(name).map(|v: &HeaderValue| v.to_str().unwrap().to_string());
+        (header("deprecation"), header("sunset"))
+    }
+
+    fn usage() -> [AtomicU64; 3] {
+        [const { AtomicU64::new(0) }; 3]
+    }
+
+    #[test]
+    fn unflagged_replies_get_no_headers() {
+        let usage = usage();
+        assert_eq!(headers(&usage, &[]), (None, None));
+        assert_eq!(headers(&usage, &["unknown"]), (None, None));
+    }
+
+    #[test]
+    fn deprecation_without_a_sunset_date() {
+        let usage = usage();
+        assert_eq!(headers(&usage, &["a"]), (Some("true".to_string()), None));
+        assert_eq!(usage[0].load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn earliest_sunset_wins() {
+        let usage = usage();
+        assert_eq!(
+            headers(&usage, &["b"]),
+            (Some("true".to_string()), Some("Wed, 30 Jun 2027 00:00:00 GMT".to_string()))
+        );
+        assert_eq!(
+            headers(&usage, &["a", "b", "c"]),
+            (Some("true".to_string()), Some("Fri, 15 Jan 2027 00:00:00 GMT".to_string()))
+        );
+        assert_eq!(usage.iter().map(|count| count.load(Ordering::Relaxed)).collect::<Vec<_>>(), [1, 2, 1]);
+    }
+
+    #[test]
+    fn contact_fields_come_from_the_registry() {
+        assert_eq!(
+            contact_fields().collect::<Vec<_>>(),
+            [
+                "first_name",
+                "last_name",
+                "phone_number",
+                "attribution.utm_source",
+                "attribution.utm_medium",
+                "attribution.utm_campaign"
+            ]
+        );
+    }
+}
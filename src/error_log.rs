@@ -0,0 +1,197 @@
+use chrono::{SecondsFormat, Utc};
+use personal_api::models::ErrorEntry;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Metadata, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+// Bounded buffer of the most recent errors, newest last
+#[derive(Clone)]
+pub struct ErrorBuffer {
+    entries: Arc<Mutex<VecDeque<ErrorEntry>>>,
+    capacity: usize,
+}
+
+impl ErrorBuffer {
+    pub fn new(capacity: usize) -> Self {
+        ErrorBuffer {
+            entries: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    pub fn entries(&self, route: Option<&str>) -> Vec<ErrorEntry> {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|entry| route.is_none() || entry.route.as_deref() == route)
+            .cloned()
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    fn push(&self, entry: ErrorEntry) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    pub fn layer(&self) -> ErrorBufferLayer {
+        ErrorBufferLayer { buffer: self.clone() }
+    }
+}
+
+// Per-layer filter for ErrorBufferLayer: only ERROR events, but spans of
+// every level so the request fields of INFO request spans stay visible.
+// A plain LevelFilter::ERROR would hide those spans from the layer.
+pub fn error_events(metadata: &Metadata<'_>) -> bool {
+    metadata.is_span() || *metadata.level() == Level::ERROR
+}
+
+// Tracing layer feeding every error! event into an ErrorBuffer, tagged with
+// the request_id and route fields of the spans it was emitted in
+pub struct ErrorBufferLayer {
+    buffer: ErrorBuffer,
+}
+
+// request_id/route recorded on a span, stored in its extensions
+#[derive(Default)]
+struct RequestFields {
+    message: Option<String>,
+    request_id: Option<String>,
+    route: Option<String>,
+}
+
+impl Visit for RequestFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record(field, value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record(field, format!("{:?}", value));
+    }
+}
+
+impl RequestFields {
+    fn record(&mut self, field: &Field, value: String) {
+        match field.name() {
+            "message" => self.message = Some(value),
+            "request_id" => self.request_id = Some(value),
+            "route" => self.route = Some(value),
+            _ => {}
+        }
+    }
+}
+
+impl<S> Layer<S> for ErrorBufferLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = RequestFields::default();
+        attrs.record(&mut fields);
+        if fields.request_id.is_some() || fields.route.is_some() {
+            if let Some(span) = ctx.span(id) {
+                span.extensions_mut().insert(fields);
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut fields = RequestFields::default();
+        event.record(&mut fields);
+
+        // The innermost span that knows a value wins
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope {
+                if let Some(span_fields) = span.extensions().get::<RequestFields>() {
+                    if fields.request_id.is_none() {
+                        fields.request_id = span_fields.request_id.clone();
+                    }
+                    if fields.route.is_none() {
+                        fields.route = span_fields.route.clone();
+                    }
+                }
+            }
+        }
+
+        self.buffer.push(ErrorEntry {
+            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, false),
+            request_id: fields.request_id,
+            route: fields.route,
+            target: event.metadata().target().to_string(),
+            message: fields.message.unwrap_or_default(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::filter::filter_fn;
+    use tracing_subscriber::prelude::*;
+
+    // Run `f` with the buffer's layer registered the way main registers it
+    fn capture(buffer: &ErrorBuffer, f: impl FnOnce()) {
+        let subscriber = tracing_subscriber::registry().with(buffer.layer().with_filter(filter_fn(error_events)));
+        tracing::subscriber::with_default(subscriber, f);
+    }
+
+    fn messages(entries: &[ErrorEntry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.message.as_str()).collect()
+    }
+
+    #[test]
+    fn buffer_keeps_the_newest_errors_up_to_capacity() {
+        let buffer = ErrorBuffer::new(3);
+        capture(&buffer, || {
+            for i in 1..=5 {
+                tracing::error!("error {}", i);
+            }
+        });
+        assert_eq!(messages(&buffer.entries(None)), ["error 3", "error 4", "error 5"]);
+    }
+
+    #[test]
+    fn only_error_events_are_kept() {
+        let buffer = ErrorBuffer::new(10);
+        capture(&buffer, || {
+            tracing::warn!("warning");
+            tracing::info!("info");
+            tracing::error!("error");
+        });
+        assert_eq!(messages(&buffer.entries(None)), ["error"]);
+    }
+
+    #[test]
+    fn errors_are_tagged_from_info_spans_and_filtered_by_route() {
+        let buffer = ErrorBuffer::new(10);
+        capture(&buffer, || {
+            let contact = tracing::info_span!("contact", request_id = "req-1", route = "/api/contact");
+            contact.in_scope(|| {
+                tracing::error!("email failed");
+                tracing::info_span!("send").in_scope(|| tracing::error!("nested"));
+            });
+            tracing::info_span!("resume", route = "/api/resume").in_scope(|| tracing::error!("missing file"));
+            tracing::error!("no span");
+        });
+
+        let contact = buffer.entries(Some("/api/contact"));
+        assert_eq!(messages(&contact), ["email failed", "nested"]);
+        assert!(contact.iter().all(|entry| entry.request_id.as_deref() == Some("req-1")));
+        assert_eq!(messages(&buffer.entries(Some("/api/resume"))), ["missing file"]);
+        assert_eq!(buffer.entries(None).len(), 4);
+        assert!(buffer.entries(Some("/api/other")).is_empty());
+    }
+}
@@ -0,0 +1,174 @@
+// Fault injection for exercising failure paths on a running instance. Only
+// compiled in with the `fault-injection` feature, and even then only active
+// when APP_ENV=development; release builds without the feature always report
+// no faults and have no admin route to enable them.
+
+#[cfg(feature = "fault-injection")]
+mod enabled {
+    use personal_api::models::ErrorMessage;
+    use serde::{Deserialize, Serialize};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+    use warp::{Filter, Reply};
+
+    // Faults expire on their own so a forgotten one can't linger
+    const MAX_TTL_SECS: u64 = 3600;
+
+    static FAULTS: Mutex<Option<ActiveFaults>> = Mutex::new(None);
+    static EMAIL_CALLS: AtomicU64 = AtomicU64::new(0);
+
+    struct ActiveFaults {
+        email_failure_percent: u8,
+        fail_readiness: bool,
+        expires_at: Instant,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    pub struct FaultRequest {
+        #[serde(rename = "emailFailurePercent", default)]
+        email_failure_percent: u8,
+        #[serde(rename = "failReadiness", default)]
+        fail_readiness: bool,
+        #[serde(rename = "ttlSeconds")]
+        ttl_seconds: u64,
+    }
+
+    #[derive(Debug, Serialize)]
+    struct FaultStatus {
+        #[serde(rename = "emailFailurePercent")]
+        email_failure_percent: u8,
+        #[serde(rename = "failReadiness")]
+        fail_readiness: bool,
+        #[serde(rename = "expiresInSeconds")]
+        expires_in_seconds: u64,
+    }
+
+    // Read once at startup and passed to `routes`
+    pub fn allowed() -> bool {
+        std::env::var("APP_ENV").map(|v| v == "development").unwrap_or(false)
+    }
+
+    fn with_active<T>(f: impl FnOnce(&ActiveFaults) -> T) -> Option<T> {
+        let mut faults = FAULTS.lock().unwrap();
+        if faults.as_ref().is_some_and(|active| active.expires_at <= Instant::now()) {
+            tracing::info!("Injected faults expired");
+            *faults = None;
+        }
+        faults.as_ref().map(f)
+    }
+
+    // Fails the given percentage of calls, spread evenly rather than randomly
+    // so 50% really is every other call
+    pub fn email_should_fail() -> bool {
+        let percent = with_active(|active| active.email_failure_percent as u64).unwrap_or(0);
+        if percent == 0 {
+            return false;
+        }
+        let call = EMAIL_CALLS.fetch_add(1, Ordering::Relaxed) + 1;
+        (call * percent) / 100 > ((call - 1) * percent) / 100
+    }
+
+    pub fn readiness_should_fail() -> bool {
+        with_active(|active| active.fail_readiness).unwrap_or(false)
+    }
+
+    fn status() -> Option<FaultStatus> {
+        with_active(|active| FaultStatus {
+            email_failure_percent: active.email_failure_percent,
+            fail_readiness: active.fail_readiness,
+            expires_in_seconds: active.expires_at.saturating_duration_since(Instant::now()).as_secs(),
+        })
+    }
+
+    // GET/POST/DELETE /api/admin/faults, not found unless `allowed`
+    pub fn routes(
+        token: Option<Arc<String>>,
+        allowed: bool,
+    ) -> impl Filter<Extract = (warp::reply::Response,), Error = warp::Rejection> + Clone {
+        warp::path!("api" / "admin" / "faults")
+            .and(warp::any().and_then(move || async move {
+                if allowed {
+                    Ok(())
+                } else {
+                    Err(warp::reject::not_found())
+                }
+            }))
+            .untuple_one()
+            .and(crate::admin::require_token(token))
+            .and(
+                warp::get()
+                    .map(|| warp::reply::json(&status()).into_response())
+                    .or(warp::post()
+                        .and(warp::body::content_length_limit(1024))
+                        .and(warp::body::json())
+                        .map(set_faults))
+                    .unify()
+                    .or(warp::delete().map(|| {
+                        *FAULTS.lock().unwrap() = None;
+                        tracing::warn!("Injected faults cleared");
+                        warp::reply::with_status(warp::reply(), warp::http::StatusCode::NO_CONTENT)
+                            .into_response()
+                    }))
+                    .unify(),
+            )
+            .recover(crate::admin::handle_rejection)
+            .unify()
+    }
+
+    fn set_faults(request: FaultRequest) -> warp::reply::Response {
+        if request.email_failure_percent > 100 || request.ttl_seconds == 0 || request.ttl_seconds > MAX_TTL_SECS {
+            return warp::reply::with_status(
+                warp::reply::json(&ErrorMessage::new(format!(
+                    "emailFailurePercent must be 0-100 and ttlSeconds 1-{}",
+                    MAX_TTL_SECS
+                ))),
+                warp::http::StatusCode::BAD_REQUEST,
+            )
+            .into_response();
+        }
+
+        tracing::warn!(
+            "Injecting faults for {}s: email failure {}%, readiness failure {}",
+            request.ttl_seconds,
+            request.email_failure_percent,
+            request.fail_readiness
+        );
+        EMAIL_CALLS.store(0, std::sync::atomic::Ordering::Relaxed);
+        *FAULTS.lock().unwrap() = Some(ActiveFaults {
+            email_failure_percent: request.email_failure_percent,
+            fail_readiness: request.fail_readiness,
+            expires_at: Instant::now() + Duration::from_secs(request.ttl_seconds),
+        });
+
+        warp::reply::json(&status()).into_response()
+    }
+}
+
+#[cfg(feature = "fault-injection")]
+pub use enabled::{allowed, email_should_fail, readiness_should_fail, routes};
+
+#[cfg(not(feature = "fault-injection"))]
+pub fn allowed() -> bool {
+    false
+}
+
+#[cfg(not(feature = "fault-injection"))]
+pub fn email_should_fail() -> bool {
+    false
+}
+
+#[cfg(not(feature = "fault-injection"))]
+pub fn readiness_should_fail() -> bool {
+    false
+}
+
+#[cfg(not(feature = "fault-injection"))]
+pub fn routes(
+    _token: Option<std::sync::Arc<String>>,
+    _allowed: bool,
+) -> impl warp::Filter<Extract = (warp::reply::Response,), Error = warp::Rejection> + Clone {
+    use warp::Filter;
+    warp::any().and_then(|| async { Err::<warp::reply::Response, _>(warp::reject::not_found()) })
+}
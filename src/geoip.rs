@@ -0,0 +1,245 @@
+use maxminddb::{geoip2, Reader};
+use personal_api::models::GeoLocation;
+use std::env;
+use std::fs;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::time::{Duration, SystemTime};
+
+// How often the database file is checked for a new copy
+const RELOAD_INTERVAL: Duration = Duration::from_secs(60);
+
+// GeoLite2 City/Country lookups from GEOIP_DB_PATH. A background task reloads
+// the database when the file's mtime changes, so a weekly geoipupdate needs
+// no restart. Lookups never touch the file system.
+pub struct GeoIp {
+    path: String,
+    database: RwLock<Option<Arc<Reader<Vec<u8>>>>>,
+    // The mtime last tried, loaded or not, so a bad copy is parsed only once
+    checked: Mutex<Option<SystemTime>>,
+}
+
+impl GeoIp {
+    pub fn from_env() -> Option<Self> {
+        let path = env::var("GEOIP_DB_PATH").ok().filter(|v| !v.trim().is_empty())?;
+        Some(Self::open(path.trim()))
+    }
+
+    // Loads the database now if it is there; a missing file is picked up by `spawn_reloader`
+    pub fn open(path: &str) -> Self {
+        let geoip = GeoIp {
+            path: path.to_string(),
+            database: RwLock::new(None),
+            checked: Mutex::new(None),
+        };
+        if let Err(e) = fs::metadata(&geoip.path) {
+            tracing::warn!("GeoIP database {} unavailable: {}", geoip.path, e);
+        }
+        geoip.reload();
+        geoip
+    }
+
+    // Check the file every RELOAD_INTERVAL, one reload at a time, off the async workers
+    pub fn spawn_reloader(self: &Arc<Self>) {
+        let geoip = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(RELOAD_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let geoip = geoip.clone();
+                if let Err(e) = tokio::task::spawn_blocking(move || geoip.reload()).await {
+                    tracing::error!("GeoIP reload task failed: {}", e);
+                }
+            }
+        });
+    }
+
+    // Never fails: a missing database, unknown IP or bad record is just None
+    pub fn lookup(&self, ip: IpAddr) -> Option<GeoLocation> {
+        let reader = self.database.read().unwrap().clone()?;
+        let city = match reader.lookup(ip).and_then(|result| result.decode::<geoip2::City>()) {
+            Ok(city) => city?,
+            Err(e) => {
+                tracing::warn!("GeoIP lookup failed for {}: {}", ip, e);
+                return None;
+            }
+        };
+
+        let location = GeoLocation {
+            country_code: city.country.iso_code.map(str::to_string),
+            city: city.city.names.english.map(str::to_string),
+        };
+        location.summary().map(|_| location)
+    }
+
+    // Load the file if its mtime is new. While it is missing or half-written
+    // the last good copy keeps serving.
+    fn reload(&self) {
+        let mut checked = self.checked.lock().unwrap_or_else(PoisonError::into_inner);
+        let modified = match fs::metadata(&self.path).and_then(|m| m.modified()) {
+            Ok(modified) => modified,
+            Err(e) => {
+                if checked.take().is_some() {
+                    tracing::warn!("GeoIP database {} unavailable, keeping the loaded copy: {}", self.path, e);
+                }
+                return;
+            }
+        };
+        if *checked == Some(modified) {
+            return;
+        }
+        *checked = Some(modified);
+
+        match Reader::open_readfile(&self.path) {
+            Ok(reader) => {
+                tracing::info!("Loaded GeoIP database {} ({})", self.path, reader.metadata().database_type);
+                *self.database.write().unwrap() = Some(Arc::new(reader));
+            }
+            Err(e) => tracing::warn!("Could not load GeoIP database {}: {}", self.path, e),
+        }
+    }
+}
+
+// IP addresses of reverse proxies allowed to set X-Forwarded-For, from
+// TRUSTED_PROXIES. Without any, only the socket address is used.
+pub fn trusted_proxies_from_env() -> Vec<IpAddr> {
+    env::var("TRUSTED_PROXIES")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| {
+            let ip = entry.parse::<IpAddr>().ok();
+            if ip.is_none() {
+                tracing::warn!("Ignoring invalid TRUSTED_PROXIES entry: {}", entry);
+            }
+            ip
+        })
+        .collect()
+}
+
+// The client's IP: the socket address, or when that is a trusted proxy the
+// right-most X-Forwarded-For entry that isn't one
+pub fn client_ip(remote: Option<SocketAddr>, forwarded_for: Option<&str>, trusted: &[IpAddr]) -> Option<IpAddr> {
+    let mut ip = remote?.ip();
+    if !trusted.contains(&ip) {
+        return Some(ip);
+    }
+
+    for hop in forwarded_for.unwrap_or_default().rsplit(',') {
+        match hop.trim().parse::<IpAddr>() {
+            Ok(hop) => {
+                ip = hop;
+                if !trusted.contains(&hop) {
+                    break;
+                }
+            }
+            Err(_) => break,
+        }
+    }
+    Some(ip)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    // testdata/geoip.mmdb maps every IPv4 address to London, GB
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/geoip.mmdb");
+
+    fn remote(ip: &str) -> Option<SocketAddr> {
+        Some(SocketAddr::new(ip.parse().unwrap(), 443))
+    }
+
+    fn ips(list: &[&str]) -> Vec<IpAddr> {
+        list.iter().map(|ip| ip.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn lookup_reads_country_and_city() {
+        let geoip = GeoIp::open(FIXTURE);
+        let location = geoip.lookup("81.2.69.142".parse().unwrap()).unwrap();
+        assert_eq!(location.country_code.as_deref(), Some("GB"));
+        assert_eq!(location.city.as_deref(), Some("London"));
+    }
+
+    #[test]
+    fn lookup_without_a_usable_database_is_empty() {
+        let dir = TempDir::new();
+        let missing = GeoIp::open(&dir.path("missing.mmdb"));
+        assert!(missing.lookup("81.2.69.142".parse().unwrap()).is_none());
+
+        let garbage = GeoIp::open(&dir.write("garbage.mmdb", b"not a database"));
+        assert!(garbage.lookup("81.2.69.142".parse().unwrap()).is_none());
+
+        // The fixture is IPv4-only
+        assert!(GeoIp::open(FIXTURE).lookup("2001:db8::1".parse().unwrap()).is_none());
+    }
+
+    // Give the file its own mtime so each change is seen regardless of timestamp granularity
+    fn replace(path: &str, contents: &[u8], seconds: u64) {
+        fs::write(path, contents).unwrap();
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_770_000_000 + seconds);
+        fs::File::options().write(true).open(path).unwrap().set_modified(modified).unwrap();
+    }
+
+    #[test]
+    fn database_appears_once_the_file_does() {
+        let dir = TempDir::new();
+        let path = dir.path("geoip.mmdb");
+        let geoip = GeoIp::open(&path);
+        assert!(geoip.lookup("81.2.69.142".parse().unwrap()).is_none());
+
+        replace(&path, &fs::read(FIXTURE).unwrap(), 0);
+        assert!(geoip.lookup("81.2.69.142".parse().unwrap()).is_none(), "lookups never reload");
+        geoip.reload();
+        assert!(geoip.lookup("81.2.69.142".parse().unwrap()).is_some());
+    }
+
+    #[test]
+    fn last_good_database_survives_bad_or_missing_copies() {
+        let dir = TempDir::new();
+        let path = dir.path("geoip.mmdb");
+        replace(&path, &fs::read(FIXTURE).unwrap(), 0);
+        let geoip = GeoIp::open(&path);
+
+        replace(&path, b"not a database", 1);
+        geoip.reload();
+        assert!(geoip.lookup("81.2.69.142".parse().unwrap()).is_some());
+        assert_eq!(*geoip.checked.lock().unwrap(), Some(fs::metadata(&path).unwrap().modified().unwrap()));
+
+        fs::remove_file(&path).unwrap();
+        geoip.reload();
+        assert!(geoip.lookup("81.2.69.142".parse().unwrap()).is_some());
+        assert!(geoip.checked.lock().unwrap().is_none(), "whatever appears next is loaded");
+    }
+
+    #[test]
+    fn forwarded_for_is_ignored_from_untrusted_peers() {
+        let trusted = ips(&["10.0.0.1"]);
+        let ip = client_ip(remote("203.0.113.9"), Some("198.51.100.7"), &trusted);
+        assert_eq!(ip, Some("203.0.113.9".parse().unwrap()));
+        let ip = client_ip(remote("10.0.0.1"), Some("198.51.100.7"), &[]);
+        assert_eq!(ip, Some("10.0.0.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn trusted_proxies_are_skipped_right_to_left() {
+        let trusted = ips(&["10.0.0.1", "10.0.0.2"]);
+
+        // A spoofed left-most entry is never reached
+        let ip = client_ip(remote("10.0.0.1"), Some("1.1.1.1, 198.51.100.7, 10.0.0.2"), &trusted);
+        assert_eq!(ip, Some("198.51.100.7".parse().unwrap()));
+
+        // A garbled hop stops the walk at the last good address
+        let ip = client_ip(remote("10.0.0.1"), Some("198.51.100.7, junk, 10.0.0.2"), &trusted);
+        assert_eq!(ip, Some("10.0.0.2".parse().unwrap()));
+
+        // Without the header the proxy itself is the client
+        assert_eq!(client_ip(remote("10.0.0.1"), None, &trusted), Some("10.0.0.1".parse().unwrap()));
+        assert_eq!(client_ip(None, Some("198.51.100.7"), &trusted), None);
+    }
+}
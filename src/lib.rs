@@ -0,0 +1,28 @@
+// Request and response models shared by the server and by Rust callers.
+// `models` has no server dependencies; the optional `client` feature adds a
+// typed HTTP client built on the same types so the two can't drift apart.
+
+pub mod models;
+
+#[cfg(feature = "client")]
+pub mod client;
+
+// Basic input sanitization to prevent XSS and other attacks
+pub fn sanitize_input(input: &str) -> String {
+    input
+        .chars()
+        .filter(|c| !c.is_control() && *c != '\0')
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+// sanitize_input for free text, keeping its line breaks and tabs
+pub fn sanitize_multiline(input: &str) -> String {
+    input
+        .chars()
+        .filter(|c| !c.is_control() || matches!(c, '\n' | '\t'))
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
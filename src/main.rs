@@ -1,56 +1,154 @@
-use warp::Filter;
-use serde::{Deserialize, Serialize};
-use std::path::Path;
+use warp::{Filter, Reply};
 use std::fs;
 use std::env;
 use validator::Validate;
-use reqwest::Client;
-
-#[derive(Debug, Deserialize, Validate)]
-struct ContactForm {
-    #[validate(email)]
-    email: String,
-    #[validate(length(min = 1, max = 100))]
-    #[serde(rename = "firstName")]
-    first_name: String,
-    #[validate(length(min = 1, max = 100))]
-    #[serde(rename = "lastName")]
-    last_name: String,
-    #[validate(length(min = 10, max = 20))]
-    #[serde(rename = "phoneNumber")]
-    phone_number: String,
-    #[validate(length(min = 1, max = 1000))]
-    message: String,
+use sha2::{Digest, Sha256};
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use futures_util::StreamExt;
+use tracing_subscriber::prelude::*;
+use warp::hyper::body::Buf;
+
+mod admin;
+mod brevo;
+mod cache_control;
+mod country_policy;
+mod deprecations;
+mod error_log;
+mod faults;
+mod geoip;
+mod outbound;
+mod query;
+mod rate_limit;
+mod redirects;
+mod resume;
+mod routing;
+mod stats;
+mod submissions;
+mod translator;
+
+#[cfg(test)]
+mod test_support;
+
+use brevo::BrevoMailer;
+use personal_api::models::{
+    Attribution, ContactError, ContactForm, ContactResponse, ContactResponseV2, DeliveryStatus, ErrorMessage,
+    GeoLocation, HealthStatus, ReadyChecks, ReadyStatus, CONTACT_V2_MEDIA_TYPE,
+};
+use personal_api::sanitize_input;
+
+// Origins allowed to call the API from a browser
+const ALLOWED_ORIGINS: &[&str] = &[
+    "http://localhost:3000",
+    "http://localhost:3001",
+    "http://localhost:8080",
+    "http://localhost:8081",
+    "http://127.0.0.1:3000",
+    "http://127.0.0.1:3001",
+    "http://127.0.0.1:8080",
+    "http://127.0.0.1:8081",
+    "https://michaelhenry.me",
+];
+
+// Number of recent error events kept for /api/admin/errors
+const ERROR_BUFFER_CAPACITY: usize = 200;
+
+// Distinct rejected origins remembered before the rest are lumped together
+const MAX_REJECTED_ORIGINS: usize = 100;
+
+const CONTACT_SENT_MESSAGE: &str = "Thank you for your message. We'll get back to you soon!";
+const CONTACT_QUEUED_MESSAGE: &str = "Thank you for your message. It has been saved and will be passed on.";
+
+// Largest contact form body read; the form's field limits keep real ones far smaller
+const MAX_CONTACT_BODY_BYTES: u64 = 16 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ResponseVersion {
+    V1,
+    V2,
 }
 
-#[derive(Debug, Serialize)]
-struct ContactResponse {
-    success: bool,
+// Outcome of a stored contact submission; every response shape is built from
+// this. The message is safe once stored, so a failed email is still a success:
+// it is resent at the next startup or from /api/contact/retry-failed, and a
+// client retry would only store it twice.
+#[derive(Debug)]
+struct ContactOutcome {
+    contact_id: String,
+    request_id: String,
+    delivery: DeliveryStatus,
     message: String,
-    id: String,
+    received_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize)]
-struct BrevoEmail {
-    sender: BrevoSender,
-    to: Vec<BrevoRecipient>,
-    subject: String,
-    #[serde(rename = "htmlContent")]
-    html_content: String,
+impl From<&ContactOutcome> for ContactResponse {
+    fn from(outcome: &ContactOutcome) -> Self {
+        ContactResponse {
+            success: true,
+            message: outcome.message.clone(),
+            id: outcome.contact_id.clone(),
+        }
+    }
 }
 
-#[derive(Debug, Serialize)]
-struct BrevoSender {
-    name: String,
-    email: String,
+impl From<&ContactOutcome> for ContactResponseV2 {
+    fn from(outcome: &ContactOutcome) -> Self {
+        let legacy = ContactResponse::from(outcome);
+        ContactResponseV2 {
+            success: legacy.success,
+            message: legacy.message,
+            id: legacy.id,
+            request_id: outcome.request_id.clone(),
+            delivery: outcome.delivery,
+            received_at: outcome.received_at.to_rfc3339_opts(SecondsFormat::Secs, false),
+        }
+    }
 }
 
-#[derive(Debug, Serialize)]
-struct BrevoRecipient {
-    email: String,
-    name: Option<String>,
+// Everything the contact route needs besides the request itself
+struct ContactContext {
+    mailer: Option<Arc<BrevoMailer>>,
+    country_policy: Arc<country_policy::CountryPolicy>,
+    submissions: Option<Arc<submissions::SubmissionStore>>,
+    // REJECT_LEGACY_CASING: refuse snake_case field names instead of flagging them
+    reject_legacy_casing: bool,
+    // CONTACT_FORM_VARIANTS: allowed values for the form's variant field
+    form_variants: Vec<String>,
 }
 
+// Count of request bodies that ended before Content-Length was satisfied
+static INCOMPLETE_BODY_COUNT: AtomicU64 = AtomicU64::new(0);
+
+// Rejections raised while reading the contact form body
+#[derive(Debug)]
+struct IncompleteBody;
+
+impl warp::reject::Reject for IncompleteBody {}
+
+#[derive(Debug)]
+struct InvalidJsonBody(String);
+
+impl warp::reject::Reject for InvalidJsonBody {}
+
+#[derive(Debug)]
+struct UnsupportedContentType;
+
+impl warp::reject::Reject for UnsupportedContentType {}
+
+// Rejection for browser requests from an origin outside ALLOWED_ORIGINS
+#[derive(Debug)]
+struct OriginNotAllowed {
+    origin: String,
+}
+
+impl warp::reject::Reject for OriginNotAllowed {}
+
+// How often each disallowed origin has been seen, so new deployment domains
+// that need allowlisting stand out
+static REJECTED_ORIGINS: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+
 #[tokio::main]
 async fn main() {
     // Load environment variables from .env file
@@ -60,112 +158,890 @@ async fn main() {
         println!("Loaded environment variables from .env file");
     }
     
-    // Initialize tracing
-    tracing_subscriber::fmt::init();
+    // Initialize tracing; error events are also kept for the admin API
+    let error_buffer = error_log::ErrorBuffer::new(ERROR_BUFFER_CAPACITY);
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_filter(tracing_subscriber::filter::LevelFilter::INFO))
+        .with(error_buffer.layer().with_filter(tracing_subscriber::filter::filter_fn(error_log::error_events)))
+        .init();
 
+    // Verify assets before serving traffic so a bad volume mount is noticed
+    let resume_path = Arc::new(resume::path_from_env());
+    let expected_sha256 = env::var("RESUME_SHA256").ok().filter(|v| !v.trim().is_empty());
+    let strict_integrity = env::var("STRICT_INTEGRITY").map(|v| v == "true").unwrap_or(false);
+    let assets = check_asset_integrity(&resume_path, expected_sha256.as_deref());
+    match &assets {
+        Ok(()) => tracing::info!("Asset integrity check passed for {}", resume_path),
+        Err(e) => {
+            tracing::error!("Asset integrity check failed: {}", e);
+            if strict_integrity {
+                eprintln!("STRICT_INTEGRITY is enabled, refusing to start");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let faults_allowed = faults::allowed();
+    if faults_allowed {
+        tracing::warn!("Fault injection is enabled via /api/admin/faults");
+    }
+
+    // One HTTP client for every outbound call, with the connectivity settings
+    let http_client = match outbound::client_from_env() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Failed to build outbound HTTP client: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Optional machine translation for non-English messages
+    let translator = translator::from_env(http_client.clone());
+    match &translator {
+        Some(t) => tracing::info!("Message translation enabled via {}", t.provider()),
+        None => tracing::info!("Message translation not configured"),
+    }
+
+    // Who gets notified when; a bad schedule is a startup error
+    let routing = match routing::RoutingSchedule::from_env() {
+        Ok(routing) => {
+            if routing.rule_count() > 0 || routing.has_vacation() {
+                tracing::info!(
+                    "Recipient routing: {} rules{}",
+                    routing.rule_count(),
+                    if routing.has_vacation() { " and a vacation override" } else { "" }
+                );
+            }
+            routing
+        }
+        Err(e) => {
+            eprintln!("Invalid recipient routing config: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Brevo sender, built once so per-message work is only the dynamic fields
+    let mailer = match BrevoMailer::from_env(http_client, translator, routing) {
+        Ok(mailer) => Some(Arc::new(mailer)),
+        Err(e) => {
+            tracing::error!("Email notifications disabled: {}", e);
+            None
+        }
+    };
+
+    // Re-check the Brevo sender hourly so a config mistake shows up in readiness
+    if let Some(mailer) = mailer.clone() {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(3600));
+            loop {
+                interval.tick().await;
+                mailer.verify_sender().await;
+            }
+        });
+    }
+
+    // Submissions are stored before their email is sent. Without the database
+    // the service still starts, but the contact route refuses messages.
+    let submission_store = match submissions::SubmissionStore::open_from_env() {
+        Ok(store) => Some(Arc::new(store)),
+        Err(e) => {
+            tracing::error!("Contact submissions can't be stored, the contact form is disabled: {:#}", e);
+            None
+        }
+    };
+    let storage = match &submission_store {
+        Some(store) => store.check_integrity().await,
+        None => Err(anyhow::anyhow!("the contact database could not be opened")),
+    };
+    match &storage {
+        Ok(()) => tracing::info!("Storage integrity check passed"),
+        Err(e) => {
+            tracing::error!("Storage integrity check failed: {:#}", e);
+            if strict_integrity {
+                eprintln!("STRICT_INTEGRITY is enabled, refusing to start");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // Emails that failed before a restart get another try
+    if let (Some(store), Some(mailer)) = (submission_store.clone(), mailer.clone()) {
+        tokio::spawn(async move {
+            match store.retry_failed(&mailer).await {
+                Ok(summary) if summary.attempted > 0 => tracing::info!(
+                    "Retried {} failed contact emails: {} sent, {} still failing",
+                    summary.attempted,
+                    summary.sent,
+                    summary.failed
+                ),
+                Ok(_) => {}
+                Err(e) => tracing::error!("Could not retry failed contact emails: {:#}", e),
+            }
+        });
+    }
+
+    // Admin routes get their own listener (localhost by default) unless it is
+    // configured to the same address as the public one
+    let public_addr = listen_addr("BIND_ADDR", [0, 0, 0, 0], "PORT", 3030);
+    let admin_addr = listen_addr("ADMIN_BIND_ADDR", [127, 0, 0, 1], "ADMIN_PORT", 3031);
+    let shared_listener = public_addr == admin_addr;
+
+    let app = App {
+        resume_path,
+        // Cache-Control for every response, by request path and status
+        cache_policy: Arc::new(cache_control::CacheControlPolicy::from_env()),
+        public_stats: Arc::new(stats::PublicStats::from_env()),
+        public_query_mode: query::QueryMode::from_env(),
+        integrity: Arc::new(IntegrityChecks::new(&assets, &storage)),
+        contact: Arc::new(ContactContext {
+            mailer,
+            // Per-country allow/block, applied after the GeoIP lookup
+            country_policy: Arc::new(country_policy::CountryPolicy::from_env()),
+            submissions: submission_store,
+            reject_legacy_casing: env::var("REJECT_LEGACY_CASING").map(|v| v == "true").unwrap_or(false),
+            form_variants: form_variants_from_env(),
+        }),
+        // Country and city of the submitter, when GEOIP_DB_PATH is configured
+        geoip: geoip::GeoIp::from_env().map(|geoip| {
+            let geoip = Arc::new(geoip);
+            geoip.spawn_reloader();
+            geoip
+        }),
+        trusted_proxies: Arc::new(geoip::trusted_proxies_from_env()),
+        // Per-IP limit on submissions, checked before the body is read
+        rate_limiter: rate_limit::RateLimiter::from_env().map(Arc::new),
+        // Config-driven redirects, answered before normal routing
+        redirect_table: Arc::new(redirects::RedirectTable::from_env()),
+        error_buffer,
+        admin_token: admin::token_from_env(),
+        faults_allowed,
+        base_path: base_path_from_env(),
+        shared_listener,
+    };
+    let (browser_routes, admin_routes) = routes(&app);
+    let base_path = app.base_path;
+    let browser_routes = browser_routes.with(warp::log::custom({
+        let base_path = base_path.clone();
+        move |info| tracing::info!(target: "rust-api-service", "{}", access_log_line(&info, &base_path))
+    }));
+
+    // Both listeners stop accepting and drain on the same shutdown signal
+    let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        tracing::info!("Shutdown signal received, draining connections");
+        let _ = shutdown_tx.send(true);
+    });
+    let wait_for_shutdown = |mut rx: tokio::sync::watch::Receiver<bool>| async move {
+        let _ = rx.wait_for(|stopping| *stopping).await;
+    };
+
+    let public_server = match warp::serve(browser_routes)
+        .try_bind_with_graceful_shutdown(public_addr, wait_for_shutdown(shutdown_rx.clone()))
+    {
+        Ok((addr, server)) => {
+            println!("Starting server on http://{}", addr);
+            server
+        }
+        Err(e) => {
+            eprintln!("Failed to bind {}: {}", public_addr, e);
+            std::process::exit(1);
+        }
+    };
+
+    if shared_listener {
+        public_server.await;
+        return;
+    }
+
+    let admin_log = warp::log::custom(move |info| {
+        tracing::info!(target: "rust-api-service::admin", "{}", access_log_line(&info, &base_path))
+    });
+    let admin_server = match warp::serve(admin_routes.with(admin_log))
+        .try_bind_with_graceful_shutdown(admin_addr, wait_for_shutdown(shutdown_rx))
+    {
+        Ok((addr, server)) => {
+            println!("Starting admin server on http://{}", addr);
+            server
+        }
+        Err(e) => {
+            eprintln!("Failed to bind admin listener {}: {}", admin_addr, e);
+            std::process::exit(1);
+        }
+    };
+
+    tokio::join!(public_server, admin_server);
+}
+
+// Everything the route tree is built from. main() assembles it from the
+// environment; tests build one directly.
+struct App {
+    resume_path: Arc<String>,
+    cache_policy: Arc<cache_control::CacheControlPolicy>,
+    public_stats: Arc<stats::PublicStats>,
+    public_query_mode: query::QueryMode,
+    integrity: Arc<IntegrityChecks>,
+    contact: Arc<ContactContext>,
+    geoip: Option<Arc<geoip::GeoIp>>,
+    trusted_proxies: Arc<Vec<std::net::IpAddr>>,
+    rate_limiter: Option<Arc<rate_limit::RateLimiter>>,
+    redirect_table: Arc<redirects::RedirectTable>,
+    error_buffer: error_log::ErrorBuffer,
+    admin_token: Option<Arc<String>>,
+    // /api/admin/faults is served; see faults::allowed
+    faults_allowed: bool,
+    // Every route lives under BASE_PATH when the service is mounted behind a
+    // reverse proxy at a sub-path
+    base_path: String,
+    // Admin routes are also served on the public listener
+    shared_listener: bool,
+}
+
+// What the startup integrity pass found, reported by /ready: "ok" or the reason
+#[derive(Debug, Clone)]
+struct IntegrityChecks {
+    assets: String,
+    storage: String,
+    storage_ok: bool,
+}
+
+impl IntegrityChecks {
+    fn new(assets: &Result<(), anyhow::Error>, storage: &Result<(), anyhow::Error>) -> Self {
+        let describe = |result: &Result<(), anyhow::Error>| match result {
+            Ok(()) => "ok".to_string(),
+            Err(e) => format!("{:#}", e),
+        };
+        IntegrityChecks {
+            assets: describe(assets),
+            storage: describe(storage),
+            storage_ok: storage.is_ok(),
+        }
+    }
+}
+
+type Routes = warp::filters::BoxedFilter<(warp::reply::Response,)>;
+
+// The public listener's routes, behind redirects, the origin check and cors,
+// and the admin listener's routes. Access logging is left to the caller.
+fn routes(app: &App) -> (Routes, Routes) {
     // CORS configuration for security
-    let cors = warp::cors()
-        .allow_origins(vec![
-            "http://localhost:3000",
-            "http://localhost:3001", 
-            "http://localhost:8080",
-            "http://localhost:8081",
-            "http://127.0.0.1:3000",
-            "http://127.0.0.1:3001",
-            "http://127.0.0.1:8080", 
-            "http://127.0.0.1:8081",
-            "https://michaelhenry.me",
-        ])
-        .allow_headers(vec!["content-type"])
-        .allow_methods(vec!["GET", "POST", "OPTIONS"]);
+    let cors = cors_config();
+    let cache_policy = app.cache_policy.clone();
 
     // Health check endpoint
     let health = warp::path("health")
-        .map(|| warp::reply::json(&serde_json::json!({"status": "ok"})));
+        .map(|| warp::reply::json(&HealthStatus { status: "ok".to_string() }));
 
-    // GET /api/resume - Returns PDF file
+    let resume_path = app.resume_path.clone();
+    let with_resume_path = warp::any().map(move || resume_path.clone());
+
+    // GET /api/resume/meta and /api/resume/text - Details without the download
+    let resume_details = warp::path!("api" / "resume" / "meta")
+        .and(warp::get())
+        .and(with_resume_path.clone())
+        .and_then(resume::handle_meta)
+        .or(warp::path!("api" / "resume" / "text")
+            .and(warp::get())
+            .and(with_resume_path.clone())
+            .and_then(resume::handle_text))
+        .unify();
+
+    // GET and HEAD /api/resume - Returns PDF file
     let resume = warp::path("api")
         .and(warp::path("resume"))
+        .and(warp::path::end())
+        .and(warp::get().or(warp::head()).unify())
+        .and(warp::method())
+        .and(warp::header::headers_cloned())
+        .and(with_resume_path)
+        .and_then(resume::handle_download);
+
+    // GET /api/stats/public - Coarse aggregates for the site footer
+    let stats = stats::route(app.public_stats.clone(), app.public_query_mode);
+
+    // GET /api/deprecations - What clients should migrate away from
+    let deprecations = warp::path!("api" / "deprecations")
         .and(warp::get())
-        .and_then(handle_resume);
+        .and(query::validated::<query::NoParams>(app.public_query_mode))
+        .map(|query: query::ValidatedQuery<query::NoParams>| query.reply(warp::reply::json(&deprecations::listing())));
+
+    // GET /ready - Reports whether contact submissions can be delivered
+    let ready_mailer = app.contact.mailer.clone();
+    let integrity = app.integrity.clone();
+    let ready = warp::path("ready")
+        .map(move || {
+            let (ready, email) = match &ready_mailer {
+                Some(_) if faults::readiness_should_fail() => (false, "injected readiness fault".to_string()),
+                Some(mailer) => {
+                    let status = mailer.sender_status();
+                    (status.is_ready(), status.describe())
+                }
+                None => (false, "Brevo email is not configured".to_string()),
+            };
+            // Without storage the contact route refuses messages; the resume
+            // asset doesn't affect contact delivery, so it is only reported
+            let ready = ready && integrity.storage_ok;
+            let status_code = if ready {
+                warp::http::StatusCode::OK
+            } else {
+                warp::http::StatusCode::SERVICE_UNAVAILABLE
+            };
+            warp::reply::with_status(
+                warp::reply::json(&ReadyStatus {
+                    status: if ready { "ok" } else { "unavailable" }.to_string(),
+                    checks: ReadyChecks {
+                        email,
+                        assets: integrity.assets.clone(),
+                        storage: integrity.storage.clone(),
+                    },
+                }),
+                status_code,
+            )
+        });
+
+    // Admin routes, all behind X-Admin-Token
+    let admin_token = app.admin_token.clone();
+    let admin_errors = admin::errors_route(app.error_buffer.clone(), admin_token.clone());
+
+    // POST /api/contact - Handles contact form. /api/v1 and /api/v2 pin the
+    // response shape; the unversioned path negotiates it from Accept.
+    let contact_version = warp::path("contact")
+        .and(warp::path::end())
+        .and(warp::header::optional::<String>("accept"))
+        .map(|accept: Option<String>| match accept {
+            Some(accept) if accept.contains(CONTACT_V2_MEDIA_TYPE) => ResponseVersion::V2,
+            _ => ResponseVersion::V1,
+        })
+        .or(warp::path!("v1" / "contact").map(|| ResponseVersion::V1))
+        .unify()
+        .or(warp::path!("v2" / "contact").map(|| ResponseVersion::V2))
+        .unify();
+
+    let request_id = warp::header::optional::<String>("x-request-id")
+        .map(|id: Option<String>| {
+            id.map(|id| sanitize_input(&id))
+                .filter(|id| !id.is_empty() && id.len() <= 128)
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+        });
+
+    // Country and city of the submitter, when GEOIP_DB_PATH is configured
+    let geoip = app.geoip.clone();
+    let location_proxies = app.trusted_proxies.clone();
+    let client_location = warp::addr::remote()
+        .and(warp::header::optional::<String>("x-forwarded-for"))
+        .map(move |remote, forwarded_for: Option<String>| {
+            let ip = geoip::client_ip(remote, forwarded_for.as_deref(), &location_proxies)?;
+            geoip.as_ref()?.lookup(ip)
+        });
+
+    let contact_context = app.contact.clone();
+    let with_contact_context = warp::any().map(move || contact_context.clone());
 
-    // POST /api/contact - Handles contact form
     let contact = warp::path("api")
-        .and(warp::path("contact"))
+        .and(contact_version)
+        .and(request_id)
         .and(warp::post())
-        .and(warp::body::json())
-        .and_then(handle_contact);
+        .and(rate_limit::limit(app.rate_limiter.clone(), app.trusted_proxies.clone()))
+        .and(client_location)
+        .and(contact_body())
+        .and(with_contact_context)
+        .and_then(handle_contact)
+        .recover(handle_body_rejection)
+        .recover(rate_limit::handle_rejection);
+
+    // Stored submissions, for recovering messages whose email failed
+    let mailer = app.contact.mailer.clone();
+    let submission_store = app.contact.submissions.clone();
+    let with_mailer = warp::any().map(move || mailer.clone());
+    let with_submissions = warp::any().map(move || submission_store.clone());
+    let admin_submissions = warp::path!("api" / "contact" / "submissions")
+        .and(admin::require_token(admin_token.clone()))
+        .and(warp::get())
+        .and(query::strict::<submissions::SubmissionsQuery>())
+        .and(with_submissions.clone())
+        .and_then(submissions::handle_list)
+        .or(warp::path!("api" / "contact" / "submissions" / String)
+            .and(admin::require_token(admin_token.clone()))
+            .and(warp::get())
+            .and(with_submissions.clone())
+            .and_then(submissions::handle_get))
+        .unify()
+        .or(warp::path!("api" / "contact" / "retry-failed")
+            .and(admin::require_token(admin_token.clone()))
+            .and(warp::post())
+            .and(with_submissions)
+            .and(with_mailer)
+            .and_then(submissions::handle_retry))
+        .unify()
+        .recover(admin::handle_rejection);
+
+    let admin_redirect_table = app.redirect_table.clone();
+    let admin_redirects = warp::path!("api" / "admin" / "redirects")
+        .and(admin::require_token(admin_token.clone()))
+        .and(warp::get())
+        .map(move || warp::reply::json(&admin_redirect_table.hits()))
+        .recover(admin::handle_rejection);
+
+    let admin_deprecations = warp::path!("api" / "admin" / "deprecations")
+        .and(admin::require_token(admin_token.clone()))
+        .and(warp::get())
+        .map(|| warp::reply::json(&deprecations::usage()))
+        .recover(admin::handle_rejection);
+
+    let admin_variants = warp::path!("api" / "admin" / "analytics" / "variants")
+        .and(admin::require_token(admin_token.clone()))
+        .and(warp::get())
+        .map(|| {
+            warp::reply::json(&serde_json::json!({
+                "submissions": stats::contacts_by_variant()
+            }))
+        })
+        .recover(admin::handle_rejection);
+
+    // Disallowed origins seen so far, with how often each was rejected
+    let admin_origins = warp::path!("api" / "admin" / "origins")
+        .and(admin::require_token(admin_token.clone()))
+        .and(warp::get())
+        .map(|| warp::reply::json(&*REJECTED_ORIGINS.lock().unwrap()))
+        .recover(admin::handle_rejection);
+
+    let country_policy = app.contact.country_policy.clone();
+    let admin_country_policy = warp::path!("api" / "admin" / "country-policy")
+        .and(admin::require_token(admin_token.clone()))
+        .and(warp::get())
+        .map(move || warp::reply::json(&country_policy.hits()))
+        .recover(admin::handle_rejection);
 
     // Combine all routes
-    let routes = health
+    let base_path = app.base_path.clone();
+    let prefix = path_prefix(&base_path);
+    let public_routes = prefix.clone().and(health
+        .or(ready)
+        .or(resume_details)
         .or(resume)
-        .or(contact)
-        .with(cors)
-        .with(warp::log("rust-api-service"));
+        .or(stats)
+        .or(deprecations)
+        .or(contact))
+        .map(Reply::into_response);
+    let public_routes = cache_control::apply(cache_policy.clone(), base_path.clone(), public_routes).boxed();
 
-    println!("Starting server on http://localhost:3030");
-    warp::serve(routes)
-        .run(([0, 0, 0, 0], 3030))
-        .await;
+    let admin_routes = prefix.and(admin_errors
+        .map(Reply::into_response)
+        .or(admin_redirects.map(Reply::into_response))
+        .unify()
+        .or(admin_deprecations.map(Reply::into_response))
+        .unify()
+        .or(admin_variants.map(Reply::into_response))
+        .unify()
+        .or(admin_country_policy.map(Reply::into_response))
+        .unify()
+        .or(admin_origins.map(Reply::into_response))
+        .unify()
+        .or(admin_submissions.map(Reply::into_response))
+        .unify()
+        .or(faults::routes(admin_token, app.faults_allowed))
+        .unify());
+    let admin_routes = cache_control::apply(cache_policy, base_path.clone(), admin_routes).boxed();
+
+    let browser_routes = if app.shared_listener {
+        public_routes.or(admin_routes.clone()).unify().boxed()
+    } else {
+        public_routes
+    };
+    let browser_routes = redirects::filter(app.redirect_table.clone(), base_path)
+        .or(browser_routes)
+        .unify();
+
+    // Check the origin ahead of the cors layer so rejections get a JSON body
+    // and are recorded, instead of warp's bare 403
+    let browser_routes = origin_check()
+        .and(browser_routes.with(cors))
+        .map(Reply::into_response)
+        .recover(handle_origin_rejection)
+        .unify()
+        .boxed();
+
+    (browser_routes, admin_routes)
 }
 
-async fn handle_resume() -> Result<Box<dyn warp::Reply>, warp::Rejection> {
-    let pdf_path = "assets/Michael Henry Resume - Staff Software Engineer.pdf";
-    
-    // Check if file exists
-    if !Path::new(pdf_path).exists() {
-        return Ok(Box::new(warp::reply::with_status(
-            warp::reply::json(&serde_json::json!({
-                "error": "Resume not found"
-            })),
-            warp::http::StatusCode::NOT_FOUND,
-        )));
+// Listen address from an IP and a port variable, with defaults for either
+fn listen_addr(ip_var: &str, default_ip: [u8; 4], port_var: &str, default_port: u16) -> std::net::SocketAddr {
+    let ip = match env::var(ip_var) {
+        Ok(value) => value.parse::<std::net::IpAddr>().unwrap_or_else(|_| {
+            tracing::warn!("Invalid {} '{}', using {:?}", ip_var, value, default_ip);
+            default_ip.into()
+        }),
+        Err(_) => default_ip.into(),
+    };
+    let port = match env::var(port_var) {
+        Ok(value) => value.parse::<u16>().unwrap_or_else(|_| {
+            tracing::warn!("Invalid {} '{}', using {}", port_var, value, default_port);
+            default_port
+        }),
+        Err(_) => default_port,
+    };
+    std::net::SocketAddr::new(ip, port)
+}
+
+fn base_path_from_env() -> String {
+    normalize_base_path(&env::var("BASE_PATH").unwrap_or_default())
+}
+
+// BASE_PATH normalized to "/segment/..." without a trailing slash, or "" for root
+fn normalize_base_path(value: &str) -> String {
+    let segments: Vec<String> = value
+        .split('/')
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    if segments.is_empty() {
+        String::new()
+    } else {
+        format!("/{}", segments.join("/"))
     }
+}
+
+// Filter consuming the BASE_PATH segments ahead of the route paths
+fn path_prefix(base_path: &str) -> warp::filters::BoxedFilter<()> {
+    base_path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .fold(warp::any().boxed(), |prefix, segment| {
+            prefix.and(warp::path(segment.to_string())).boxed()
+        })
+}
+
+// warp::log's access log format, with the route shown relative to BASE_PATH
+// so log lines look the same however the service is mounted
+fn access_log_line(info: &warp::log::Info<'_>, base_path: &str) -> String {
+    let path = info.path();
+    let route = match path.strip_prefix(base_path) {
+        Some("") => "/",
+        Some(route) if route.starts_with('/') => route,
+        _ => path,
+    };
+    format!(
+        "{} \"{} {} {:?}\" {} \"{}\" \"{}\" {:?}",
+        info.remote_addr().map(|addr| addr.to_string()).unwrap_or_else(|| "-".to_string()),
+        info.method(),
+        route,
+        info.version(),
+        info.status().as_u16(),
+        info.referer().unwrap_or("-"),
+        info.user_agent().unwrap_or("-"),
+        info.elapsed()
+    )
+}
+
+// Resolves on Ctrl+C, or SIGTERM on unix (what docker stop sends)
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
 
-    // Read the PDF file
-    match fs::read(pdf_path) {
-        Ok(pdf_data) => {
-            Ok(Box::new(warp::reply::with_header(
-                pdf_data,
-                "Content-Type",
-                "application/pdf",
-            )))
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
         }
-        Err(_) => {
-            Ok(Box::new(warp::reply::with_status(
-                warp::reply::json(&serde_json::json!({
-                    "error": "Failed to read resume"
-                })),
-                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
-            )))
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+fn cors_config() -> warp::cors::Builder {
+    warp::cors()
+        .allow_origins(ALLOWED_ORIGINS.to_vec())
+        .allow_headers(vec!["content-type", "x-request-id"])
+        .allow_methods(vec!["GET", "POST", "OPTIONS"])
+}
+
+// Rejects browser requests whose Origin is outside ALLOWED_ORIGINS
+fn origin_check() -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::header::optional::<String>("origin")
+        .and(warp::header::optional::<String>("referer"))
+        .and(warp::method())
+        .and_then(check_origin)
+        .untuple_one()
+}
+
+async fn check_origin(
+    origin: Option<String>,
+    referrer: Option<String>,
+    method: warp::http::Method,
+) -> Result<(), warp::Rejection> {
+    let origin = match origin {
+        Some(origin) if !ALLOWED_ORIGINS.contains(&origin.as_str()) => sanitize_input(&origin),
+        _ => return Ok(()),
+    };
+
+    let count = {
+        let mut rejected = REJECTED_ORIGINS.lock().unwrap();
+        let key = if rejected.contains_key(&origin) || rejected.len() < MAX_REJECTED_ORIGINS {
+            origin.clone()
+        } else {
+            "other".to_string()
+        };
+        let count = rejected.entry(key).or_insert(0);
+        *count += 1;
+        *count
+    };
+
+    tracing::warn!(
+        "Rejected {} request from disallowed origin {} (referrer: {}, seen {} times)",
+        if method == warp::http::Method::OPTIONS { "preflight" } else { "cross-origin" },
+        origin,
+        referrer.as_deref().map(sanitize_input).unwrap_or_else(|| "none".to_string()),
+        count
+    );
+
+    Err(warp::reject::custom(OriginNotAllowed { origin }))
+}
+
+async fn handle_origin_rejection(err: warp::Rejection) -> Result<warp::reply::Response, warp::Rejection> {
+    match err.find::<OriginNotAllowed>() {
+        Some(OriginNotAllowed { origin }) => Ok(warp::reply::with_status(
+            warp::reply::json(&ErrorMessage::new("origin not allowed").with_detail("origin", origin)),
+            warp::http::StatusCode::FORBIDDEN,
+        ).into_response()),
+        None => Err(err),
+    }
+}
+
+// The contact form's JSON body. Like warp::body::json, it needs a
+// Content-Length within the limit and takes a missing Content-Type as JSON.
+fn contact_body() -> impl Filter<Extract = (serde_json::Value,), Error = warp::Rejection> + Clone {
+    warp::body::content_length_limit(MAX_CONTACT_BODY_BYTES)
+        .and(warp::header::optional::<String>("content-type"))
+        .and_then(|content_type: Option<String>| async move {
+            match content_type {
+                Some(content_type) if !is_json_media_type(&content_type) => {
+                    Err(warp::reject::custom(UnsupportedContentType))
+                }
+                _ => Ok(()),
+            }
+        })
+        .untuple_one()
+        .and(warp::body::stream())
+        .and_then(read_json_body)
+}
+
+// application/json, with or without parameters such as charset
+fn is_json_media_type(content_type: &str) -> bool {
+    content_type
+        .split(';')
+        .next()
+        .is_some_and(|essence| essence.trim().eq_ignore_ascii_case("application/json"))
+}
+
+// Read the full request body before parsing so an aborted upload is
+// rejected as a whole and never reaches the handler half-processed
+async fn read_json_body(
+    mut body: impl futures_util::Stream<Item = Result<impl Buf, warp::Error>> + Unpin,
+) -> Result<serde_json::Value, warp::Rejection> {
+    let mut bytes = Vec::new();
+
+    while let Some(chunk) = body.next().await {
+        match chunk {
+            Ok(chunk) => bytes.extend_from_slice(chunk.chunk()),
+            Err(e) => {
+                let total = INCOMPLETE_BODY_COUNT.fetch_add(1, Ordering::Relaxed) + 1;
+                tracing::warn!("Incomplete request body after {} bytes ({} total): {}", bytes.len(), total, e);
+                return Err(warp::reject::custom(IncompleteBody));
+            }
         }
     }
+
+    serde_json::from_slice(&bytes)
+        .map_err(|e| warp::reject::custom(InvalidJsonBody(e.to_string())))
+}
+
+// Turn body read failures into JSON errors; other rejections pass through
+async fn handle_body_rejection(err: warp::Rejection) -> Result<warp::reply::Response, warp::Rejection> {
+    if err.find::<IncompleteBody>().is_some() {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&ContactError::new("The request body was incomplete").with_code("INCOMPLETE_BODY")),
+            warp::http::StatusCode::BAD_REQUEST,
+        ).into_response());
+    }
+
+    if err.find::<warp::reject::PayloadTooLarge>().is_some() {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(
+                &ContactError::new(format!("The request body is larger than {} bytes", MAX_CONTACT_BODY_BYTES))
+                    .with_code("BODY_TOO_LARGE"),
+            ),
+            warp::http::StatusCode::PAYLOAD_TOO_LARGE,
+        ).into_response());
+    }
+
+    if err.find::<warp::reject::LengthRequired>().is_some() {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&ContactError::new("The request needs a Content-Length header").with_code("LENGTH_REQUIRED")),
+            warp::http::StatusCode::LENGTH_REQUIRED,
+        ).into_response());
+    }
+
+    if err.find::<UnsupportedContentType>().is_some() {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(
+                &ContactError::new("The request body must be application/json").with_code("UNSUPPORTED_MEDIA_TYPE"),
+            ),
+            warp::http::StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        ).into_response());
+    }
+
+    if let Some(InvalidJsonBody(e)) = err.find::<InvalidJsonBody>() {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&ContactError::new("Invalid request body").with_errors(e)),
+            warp::http::StatusCode::BAD_REQUEST,
+        ).into_response());
+    }
+
+    Err(err)
+}
+
+#[tracing::instrument(skip_all, fields(request_id = %request_id, route = "/api/contact"))]
+async fn handle_contact(
+    version: ResponseVersion,
+    request_id: String,
+    location: Option<GeoLocation>,
+    body: serde_json::Value,
+    context: Arc<ContactContext>,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    // Track older forms still posting snake_case so they can be migrated.
+    // Every response to such a form is flagged, errors included.
+    let legacy_fields = legacy_fields_used(&body);
+    if !legacy_fields.is_empty() {
+        tracing::warn!("Contact form used legacy snake_case fields: {}", legacy_fields.join(", "));
+    }
+
+    let mut reply = contact_response(version, request_id, location, body, &legacy_fields, &context).await;
+    deprecations::annotate(&mut reply, &deprecated_items(&legacy_fields));
+    Ok(reply)
 }
 
-async fn handle_contact(form: ContactForm) -> Result<impl warp::Reply, warp::Rejection> {
+async fn contact_response(
+    version: ResponseVersion,
+    request_id: String,
+    location: Option<GeoLocation>,
+    body: serde_json::Value,
+    legacy_fields: &[&str],
+    context: &ContactContext,
+) -> warp::reply::Response {
+    if !legacy_fields.is_empty() && context.reject_legacy_casing {
+        return warp::reply::with_status(
+            warp::reply::json(
+                &ContactError::new("snake_case field names are no longer accepted, use firstName, lastName and phoneNumber")
+                    .with_errors(serde_json::json!({ "legacyFields": legacy_fields })),
+            ),
+            warp::http::StatusCode::BAD_REQUEST,
+        ).into_response();
+    }
+
+    let mut form: ContactForm = match serde_json::from_value(body) {
+        Ok(form) => form,
+        Err(e) => {
+            return warp::reply::with_status(
+                warp::reply::json(&ContactError::new("Invalid request body").with_errors(e.to_string())),
+                warp::http::StatusCode::BAD_REQUEST,
+            ).into_response();
+        }
+    };
+
+    // The honeypot field is hidden from real users, so a filled one means a
+    // bot. It gets the usual success response so there's nothing to adapt to.
+    if form.website.as_deref().is_some_and(|website| !website.trim().is_empty()) {
+        let outcome = ContactOutcome {
+            contact_id: uuid::Uuid::new_v4().to_string(),
+            request_id,
+            delivery: DeliveryStatus::Sent,
+            message: CONTACT_SENT_MESSAGE.to_string(),
+            received_at: Utc::now(),
+        };
+        tracing::warn!("Dropped contact form as spam (honeypot filled), ID: {}", outcome.contact_id);
+        return contact_reply(version, &outcome);
+    }
+
     // Validate the form data
     if let Err(validation_errors) = form.validate() {
-        return Ok(warp::reply::with_status(
-            warp::reply::json(&serde_json::json!({
-                "success": false,
-                "message": "Validation failed",
-                "errors": validation_errors
-            })),
+        return warp::reply::with_status(
+            warp::reply::json(&ContactError::new("Validation failed").with_errors(validation_errors)),
             warp::http::StatusCode::BAD_REQUEST,
-        ));
+        ).into_response();
+    }
+
+    // Only allowlisted variants are accepted, so they can't be used to grow
+    // the per-variant counters without bound
+    form.variant = form.variant.take().map(|v| sanitize_input(&v)).filter(|v| !v.is_empty());
+    if let Some(variant) = &form.variant {
+        if !context.form_variants.iter().any(|allowed| allowed == variant) {
+            return warp::reply::with_status(
+                warp::reply::json(
+                    &ContactError::new("Validation failed")
+                        .with_errors(serde_json::json!({ "variant": format!("Unknown form variant '{}'", variant) })),
+                ),
+                warp::http::StatusCode::BAD_REQUEST,
+            ).into_response();
+        }
+    }
+
+    form.location = location;
+    let country_code = form.location.as_ref().and_then(|l| l.country_code.as_deref());
+    if context.country_policy.evaluate(country_code) == country_policy::CountryAction::Block {
+        tracing::warn!("Blocked contact form from country {} by COUNTRY_POLICY", country_code.unwrap_or("unknown"));
+        return warp::reply::with_status(
+            warp::reply::json(&ContactError::new("Submissions from your location are not accepted")),
+            warp::http::StatusCode::FORBIDDEN,
+        ).into_response();
     }
+    stats::record_country(country_code);
+
+    form.attribution = form.attribution.take().map(Attribution::truncated);
+
+    // Generate a unique ID for this contact submission
+    let contact_id = uuid::Uuid::new_v4().to_string();
+    let received_at = Utc::now();
+
+    // Stored before sending, so the message survives a failed email
+    let stored = match &context.submissions {
+        Some(store) => store.insert(&contact_id, &request_id, &form, received_at).await,
+        None => Err(anyhow::anyhow!("submission storage is unavailable")),
+    };
+    if let Err(e) = stored {
+        tracing::error!("Could not store contact submission {}: {:#}", contact_id, e);
+        return warp::reply::with_status(
+            warp::reply::json(
+                &ContactError::new(
+                    "Your message could not be saved right now. Please try again later or contact us directly.",
+                )
+                .with_code("STORAGE_UNAVAILABLE"),
+            ),
+            warp::http::StatusCode::SERVICE_UNAVAILABLE,
+        ).into_response();
+    }
+
+    stats::record_message_received();
+    stats::record_variant(form.variant.as_deref());
+    stats::record_utm_source(form.attribution.as_ref().and_then(|a| a.utm_source.as_deref()));
 
     // Basic input sanitization for security
     let sanitized_email = sanitize_input(&form.email);
     let sanitized_first_name = sanitize_input(&form.first_name);
     let sanitized_last_name = sanitize_input(&form.last_name);
-    let _sanitized_phone = sanitize_input(&form.phone_number);
-    let _sanitized_message = sanitize_input(&form.message);
-
-    // Generate a unique ID for this contact submission
-    let contact_id = uuid::Uuid::new_v4().to_string();
 
-    // Log the contact form submission (in production, you'd save to database)
+    // Log the contact form submission
     tracing::info!(
         "Contact form submitted: {} {} <{}> - ID: {}",
         sanitized_first_name,
@@ -175,107 +1051,991 @@ async fn handle_contact(form: ContactForm) -> Result<impl warp::Reply, warp::Rej
     );
 
     // Send email via Brevo
-    let (email_success, response_message, status_code) = match send_brevo_email(&form, &contact_id).await {
+    let send_result = match &context.mailer {
+        Some(_) if faults::email_should_fail() => Err(anyhow::anyhow!("Injected email fault")),
+        Some(mailer) => mailer.send(&form, &contact_id, received_at).await,
+        None => Err(anyhow::anyhow!("Brevo email is not configured")),
+    };
+
+    if let Some(store) = &context.submissions {
+        let error = send_result.as_ref().err().map(|e| format!("{:#}", e));
+        if let Err(e) = store.record_delivery(&contact_id, error).await {
+            tracing::error!("Could not record delivery status for contact ID {}: {:#}", contact_id, e);
+        }
+    }
+
+    let (delivery, response_message) = match send_result {
         Ok(()) => {
             tracing::info!("Contact form email sent successfully for ID: {}", contact_id);
-            (true, "Thank you for your message. We'll get back to you soon!".to_string(), warp::http::StatusCode::OK)
+            (DeliveryStatus::Sent, CONTACT_SENT_MESSAGE)
         }
         Err(e) => {
-            tracing::error!("Failed to send contact form email for ID {}: {}", contact_id, e);
-            (false, "Your message was received, but there was an issue sending the notification email. Please try again or contact us directly.".to_string(), warp::http::StatusCode::INTERNAL_SERVER_ERROR)
+            tracing::error!("Failed to send contact form email for ID {}: {:#}", contact_id, e);
+            (DeliveryStatus::Failed, CONTACT_QUEUED_MESSAGE)
         }
     };
 
-    let response = ContactResponse {
-        success: email_success,
-        message: response_message,
-        id: contact_id,
+    let outcome = ContactOutcome {
+        contact_id,
+        request_id,
+        delivery,
+        message: response_message.to_string(),
+        received_at,
     };
 
-    Ok(warp::reply::with_status(
-        warp::reply::json(&response),
-        status_code,
-    ))
+    contact_reply(version, &outcome)
 }
 
-// Send email via Brevo API
-async fn send_brevo_email(contact_form: &ContactForm, contact_id: &str) -> Result<(), anyhow::Error> {
-    tracing::debug!("Attempting to send email via Brevo for contact ID: {}", contact_id);
-    
-    let api_key = env::var("BREVO_API_KEY")
-        .map_err(|_| anyhow::anyhow!("BREVO_API_KEY environment variable not set"))?;
-    
-    let sender_email = env::var("BREVO_SENDER_EMAIL")
-        .map_err(|_| anyhow::anyhow!("BREVO_SENDER_EMAIL environment variable not set"))?;
-    
-    let sender_name = env::var("BREVO_SENDER_NAME")
-        .map_err(|_| anyhow::anyhow!("BREVO_SENDER_NAME environment variable not set"))?;
-    
-    let recipient_email = env::var("CONTACT_RECIPIENT_EMAIL")
-        .unwrap_or_else(|_| sender_email.clone());
+// The contact response in the shape the client asked for
+fn contact_reply(version: ResponseVersion, outcome: &ContactOutcome) -> warp::reply::Response {
+    match version {
+        ResponseVersion::V1 => warp::reply::json(&ContactResponse::from(outcome)).into_response(),
+        ResponseVersion::V2 => {
+            let mut reply = warp::reply::json(&ContactResponseV2::from(outcome)).into_response();
+            let headers = reply.headers_mut();
+            headers.insert("Content-Type", warp::http::HeaderValue::from_static(CONTACT_V2_MEDIA_TYPE));
+            if let Ok(value) = warp::http::HeaderValue::from_str(&outcome.request_id) {
+                headers.insert("X-Request-Id", value);
+            }
+            reply
+        }
+    }
+}
 
-    tracing::debug!("Using sender: {} <{}>, recipient: {}", sender_name, sender_email, recipient_email);
+// Legacy snake_case keys present in a contact form body; the deprecated
+// contact fields in the deprecations registry are the list of them, with
+// nested keys such as attribution.utm_source given as a dotted path
+fn legacy_fields_used(body: &serde_json::Value) -> Vec<&'static str> {
+    deprecations::contact_fields()
+        .filter(|field| body.pointer(&format!("/{}", field.replace('.', "/"))).is_some())
+        .collect()
+}
 
-    let client = Client::new();
-    
-    let html_content = format!(
-        r#"
-        <h2>New Contact Form Submission</h2>
-        <p><strong>Contact ID:</strong> {}</p>
-        <p><strong>Name:</strong> {} {}</p>
-        <p><strong>Email:</strong> {}</p>
-        <p><strong>Phone:</strong> {}</p>
-        <p><strong>Message:</strong></p>
-        <p>{}</p>
-        <hr>
-        <p><em>This message was sent from your website contact form.</em></p>
-        "#,
-        contact_id,
-        contact_form.first_name,
-        contact_form.last_name,
-        contact_form.email,
-        contact_form.phone_number,
-        contact_form.message.replace('\n', "<br>")
-    );
+// Allowed values for the contact form's variant field, from CONTACT_FORM_VARIANTS
+fn form_variants_from_env() -> Vec<String> {
+    parse_form_variants(&env::var("CONTACT_FORM_VARIANTS").unwrap_or_default())
+}
 
-    let email = BrevoEmail {
-        sender: BrevoSender {
-            name: sender_name,
-            email: sender_email,
-        },
-        to: vec![BrevoRecipient {
-            email: recipient_email,
-            name: Some("Contact Form".to_string()),
-        }],
-        subject: format!("New Contact Form Submission from {} {}", 
-                        contact_form.first_name, contact_form.last_name),
-        html_content,
-    };
+fn parse_form_variants(config: &str) -> Vec<String> {
+    config
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .collect()
+}
 
-    let response = client
-        .post("https://api.brevo.com/v3/smtp/email")
-        .header("api-key", api_key)
-        .header("Content-Type", "application/json")
-        .json(&email)
-        .send()
-        .await?;
-
-    if response.status().is_success() {
-        tracing::info!("Email sent successfully via Brevo for contact ID: {}", contact_id);
-        Ok(())
-    } else {
-        let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-        tracing::error!("Failed to send email via Brevo: {}", error_text);
-        Err(anyhow::anyhow!("Failed to send email: {}", error_text))
+// Registry items for legacy contact fields
+fn deprecated_items(legacy_fields: &[&str]) -> Vec<String> {
+    legacy_fields.iter().map(|field| format!("contact.{}", field)).collect()
+}
+
+// Check that an asset exists, is non-empty and matches the expected SHA-256 when given
+fn check_asset_integrity(path: &str, expected_sha256: Option<&str>) -> Result<(), anyhow::Error> {
+    let data = fs::read(path)
+        .map_err(|e| anyhow::anyhow!("Could not read {}: {}", path, e))?;
+
+    if data.is_empty() {
+        return Err(anyhow::anyhow!("{} is empty", path));
     }
+
+    if let Some(expected) = expected_sha256 {
+        let actual = format!("{:x}", Sha256::digest(&data));
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            return Err(anyhow::anyhow!(
+                "{} has SHA-256 {} but RESUME_SHA256 expects {}",
+                path,
+                actual,
+                expected.trim()
+            ));
+        }
+    }
+
+    Ok(())
 }
 
-// Basic input sanitization to prevent XSS and other attacks
-fn sanitize_input(input: &str) -> String {
-    input
-        .chars()
-        .filter(|c| !c.is_control() && *c != '\0')
-        .collect::<String>()
-        .trim()
-        .to_string()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, TempDir};
+
+    fn contact_context(dir: &TempDir) -> ContactContext {
+        ContactContext {
+            mailer: None,
+            country_policy: Arc::new(country_policy::CountryPolicy::parse("")),
+            submissions: Some(Arc::new(submissions::SubmissionStore::open(&dir.path("contacts.db")).unwrap())),
+            reject_legacy_casing: false,
+            form_variants: Vec::new(),
+        }
+    }
+
+    fn legacy_form() -> serde_json::Value {
+        serde_json::json!({
+            "email": "jane@example.com",
+            "first_name": "Jane",
+            "last_name": "Doe",
+            "phone_number": "+1 555 0100 200",
+            "message": "Hello"
+        })
+    }
+
+    async fn submit(context: &Arc<ContactContext>, body: serde_json::Value) -> (warp::reply::Response, serde_json::Value) {
+        let response = handle_contact(ResponseVersion::V1, "req-1".to_string(), None, body, context.clone())
+            .await
+            .unwrap();
+        let (parts, body) = response.into_parts();
+        let bytes = warp::hyper::body::to_bytes(body).await.unwrap();
+        let json = serde_json::from_slice(&bytes).unwrap();
+        (warp::reply::Response::from_parts(parts, warp::hyper::Body::empty()), json)
+    }
+
+    async fn stored(context: &ContactContext) -> Vec<personal_api::models::Submission> {
+        context.submissions.as_ref().unwrap().list(None, 10).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn legacy_field_names_are_accepted_and_flagged() {
+        let dir = TempDir::new();
+        let context = Arc::new(contact_context(&dir));
+
+        let (response, body) = submit(&context, legacy_form()).await;
+        assert_eq!(response.headers()["deprecation"], "true");
+        let submissions = stored(&context).await;
+        assert_eq!(submissions.len(), 1);
+        assert_eq!(submissions[0].id, body["id"].as_str().unwrap());
+        assert_eq!((submissions[0].first_name.as_str(), submissions[0].last_name.as_str()), ("Jane", "Doe"));
+        assert_eq!(submissions[0].phone_number, "+1 555 0100 200");
+    }
+
+    #[tokio::test]
+    async fn current_field_names_are_not_flagged() {
+        let dir = TempDir::new();
+        let context = Arc::new(contact_context(&dir));
+        let form = serde_json::json!({
+            "email": "jane@example.com",
+            "firstName": "Jane",
+            "lastName": "Doe",
+            "phoneNumber": "+1 555 0100 200",
+            "message": "Hello",
+            "attribution": { "utmSource": "linkedin", "utmMedium": "social", "landingPage": "/" }
+        });
+
+        let (response, _) = submit(&context, form).await;
+        assert!(response.headers().get("deprecation").is_none());
+        let submissions = stored(&context).await;
+        let attribution = submissions[0].attribution.as_ref().unwrap();
+        assert_eq!((attribution.utm_source.as_deref(), attribution.landing_page.as_deref()), (Some("linkedin"), Some("/")));
+    }
+
+    #[tokio::test]
+    async fn legacy_attribution_keys_are_accepted_and_flagged() {
+        let dir = TempDir::new();
+        let context = Arc::new(contact_context(&dir));
+        let form = serde_json::json!({
+            "email": "jane@example.com",
+            "firstName": "Jane",
+            "lastName": "Doe",
+            "phoneNumber": "+1 555 0100 200",
+            "message": "Hello",
+            "attribution": { "utm_source": "linkedin", "utm_campaign": "launch" }
+        });
+
+        let (response, _) = submit(&context, form.clone()).await;
+        assert_eq!(response.headers()["deprecation"], "true");
+        let attribution = stored(&context).await[0].attribution.clone().unwrap();
+        assert_eq!((attribution.utm_source.as_deref(), attribution.utm_campaign.as_deref()), (Some("linkedin"), Some("launch")));
+
+        let context = Arc::new(ContactContext { reject_legacy_casing: true, ..contact_context(&dir) });
+        let (response, body) = submit(&context, form).await;
+        assert_eq!(response.status(), warp::http::StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body["errors"]["legacyFields"],
+            serde_json::json!(["attribution.utm_source", "attribution.utm_campaign"])
+        );
+    }
+
+    #[tokio::test]
+    async fn legacy_field_names_can_be_rejected() {
+        let dir = TempDir::new();
+        let context = Arc::new(ContactContext {
+            reject_legacy_casing: true,
+            ..contact_context(&dir)
+        });
+
+        let (response, body) = submit(&context, legacy_form()).await;
+        assert_eq!(response.status(), warp::http::StatusCode::BAD_REQUEST);
+        assert_eq!(response.headers()["deprecation"], "true");
+        assert_eq!(body["errors"]["legacyFields"], serde_json::json!(["first_name", "last_name", "phone_number"]));
+        assert!(stored(&context).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn validation_errors_for_legacy_forms_are_flagged() {
+        let dir = TempDir::new();
+        let context = Arc::new(contact_context(&dir));
+        let mut form = legacy_form();
+        form["email"] = "not-an-email".into();
+
+        let (response, body) = submit(&context, form).await;
+        assert_eq!(response.status(), warp::http::StatusCode::BAD_REQUEST);
+        assert_eq!(body["message"], "Validation failed");
+        assert_eq!(response.headers()["deprecation"], "true");
+
+        let (response, _) = submit(&context, serde_json::json!({ "first_name": 1 })).await;
+        assert_eq!(response.status(), warp::http::StatusCode::BAD_REQUEST);
+        assert_eq!(response.headers()["deprecation"], "true");
+        assert!(stored(&context).await.is_empty());
+    }
+
+    // The contact body filter on a real socket, echoing the parsed JSON
+    fn body_echo_server() -> String {
+        test_support::serve(
+            warp::post()
+                .and(contact_body())
+                .map(|body: serde_json::Value| warp::reply::json(&body).into_response())
+                .recover(handle_body_rejection),
+        )
+    }
+
+    // Send raw bytes and read the whole response. With `half_close` the
+    // client stops sending after the bytes, as if the upload was cut off.
+    async fn raw_request(url: &str, request: &[u8], half_close: bool) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut stream = tokio::net::TcpStream::connect(url.trim_start_matches("http://")).await.unwrap();
+        stream.write_all(request).await.unwrap();
+        if half_close {
+            stream.shutdown().await.unwrap();
+        }
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn body_shorter_than_content_length_is_incomplete() {
+        let url = body_echo_server();
+        let before = INCOMPLETE_BODY_COUNT.load(Ordering::Relaxed);
+
+        let response = raw_request(
+            &url,
+            b"POST / HTTP/1.1\r\nHost: test\r\nContent-Type: application/json\r\nContent-Length: 40\r\n\r\n{\"email\":\"a",
+            true,
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 400"), "{}", response);
+        assert!(response.contains("\"code\":\"INCOMPLETE_BODY\""), "{}", response);
+        assert!(INCOMPLETE_BODY_COUNT.load(Ordering::Relaxed) > before);
+    }
+
+    #[tokio::test]
+    async fn truncated_json_is_invalid() {
+        let url = body_echo_server();
+        let response = raw_request(
+            &url,
+            b"POST / HTTP/1.1\r\nHost: test\r\nConnection: close\r\nContent-Type: application/json\r\nContent-Length: 11\r\n\r\n{\"email\":\"a",
+            false,
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 400"), "{}", response);
+        assert!(response.contains("\"message\":\"Invalid request body\""), "{}", response);
+        assert!(!response.contains("INCOMPLETE_BODY"), "{}", response);
+    }
+
+    #[tokio::test]
+    async fn body_size_and_content_type_are_checked() {
+        let filter = warp::post()
+            .and(contact_body())
+            .map(|body: serde_json::Value| warp::reply::json(&body).into_response())
+            .recover(handle_body_rejection);
+        let request = || warp::test::request().method("POST").path("/");
+
+        let response = request().header("content-type", "application/json; charset=utf-8").body("{\"a\":1}").reply(&filter).await;
+        assert_eq!(response.status(), warp::http::StatusCode::OK);
+        assert_eq!(response.body().as_ref(), b"{\"a\":1}");
+
+        let response = request().body("{\"a\":1}").reply(&filter).await;
+        assert_eq!(response.status(), warp::http::StatusCode::OK);
+
+        let response = request().header("content-type", "text/plain").body("{\"a\":1}").reply(&filter).await;
+        assert_eq!(response.status(), warp::http::StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let large = format!("{{\"message\":\"{}\"}}", "x".repeat(MAX_CONTACT_BODY_BYTES as usize));
+        let response = request().header("content-type", "application/json").body(large).reply(&filter).await;
+        assert_eq!(response.status(), warp::http::StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    fn outcome(delivery: DeliveryStatus) -> ContactOutcome {
+        ContactOutcome {
+            contact_id: "contact-1".to_string(),
+            request_id: "req-1".to_string(),
+            delivery,
+            message: "Thanks".to_string(),
+            received_at: DateTime::parse_from_rfc3339("2026-03-02T09:30:00.250Z").unwrap().with_timezone(&Utc),
+        }
+    }
+
+    async fn reply_json(reply: warp::reply::Response) -> serde_json::Value {
+        serde_json::from_slice(&warp::hyper::body::to_bytes(reply.into_body()).await.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn v1_and_v2_replies_come_from_the_same_outcome() {
+        let outcome = outcome(DeliveryStatus::Sent);
+
+        let v1 = contact_reply(ResponseVersion::V1, &outcome);
+        assert_eq!(v1.headers()["content-type"], "application/json");
+        assert!(v1.headers().get("x-request-id").is_none());
+        assert_eq!(
+            reply_json(v1).await,
+            serde_json::json!({ "success": true, "message": "Thanks", "id": "contact-1" })
+        );
+
+        let v2 = contact_reply(ResponseVersion::V2, &outcome);
+        assert_eq!(v2.headers()["content-type"], CONTACT_V2_MEDIA_TYPE);
+        assert_eq!(v2.headers()["x-request-id"], "req-1");
+        assert_eq!(
+            reply_json(v2).await,
+            serde_json::json!({
+                "success": true,
+                "message": "Thanks",
+                "id": "contact-1",
+                "requestId": "req-1",
+                "delivery": "sent",
+                "receivedAt": "2026-03-02T09:30:00+00:00"
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_delivery_in_both_shapes() {
+        let outcome = outcome(DeliveryStatus::Failed);
+        let v1 = ContactResponse::from(&outcome);
+        let v2 = ContactResponseV2::from(&outcome);
+
+        // Stored, so still a success; the email is resent later
+        assert_eq!((v1.success, v1.id.as_str(), v1.message.as_str()), (true, "contact-1", "Thanks"));
+        assert_eq!((v2.success, v2.id.as_str(), v2.message.as_str()), (true, "contact-1", "Thanks"));
+        assert_eq!(v2.delivery, DeliveryStatus::Failed);
+
+        let reply = contact_reply(ResponseVersion::V2, &outcome);
+        assert_eq!(reply.status(), warp::http::StatusCode::OK);
+        let body = reply_json(reply).await;
+        assert!(body.get("retry").is_none(), "{}", body);
+    }
+
+    #[test]
+    fn integrity_check_fails_for_a_missing_asset() {
+        let dir = TempDir::new();
+        let path = dir.path("missing.pdf");
+
+        let err = check_asset_integrity(&path, None).unwrap_err();
+        assert!(err.to_string().starts_with(&format!("Could not read {}", path)), "{}", err);
+    }
+
+    #[test]
+    fn integrity_check_fails_for_an_empty_asset() {
+        let dir = TempDir::new();
+        let path = dir.write("empty.pdf", b"");
+
+        let err = check_asset_integrity(&path, None).unwrap_err();
+        assert_eq!(err.to_string(), format!("{} is empty", path));
+    }
+
+    #[test]
+    fn integrity_check_compares_the_sha256() {
+        let dir = TempDir::new();
+        let path = dir.write("resume.pdf", b"%PDF-1.4 test");
+        let actual = format!("{:x}", Sha256::digest(b"%PDF-1.4 test"));
+
+        assert!(check_asset_integrity(&path, None).is_ok());
+        assert!(check_asset_integrity(&path, Some(&actual)).is_ok());
+        assert!(check_asset_integrity(&path, Some(&format!(" {} ", actual.to_uppercase()))).is_ok());
+
+        let expected = "0".repeat(64);
+        let err = check_asset_integrity(&path, Some(&expected)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("{} has SHA-256 {} but RESUME_SHA256 expects {}", path, actual, expected)
+        );
+    }
+
+    #[tokio::test]
+    async fn ready_reports_the_integrity_checks() {
+        let dir = TempDir::new();
+        let (brevo_url, _) = test_support::brevo_mock();
+        let mailer = Arc::new(test_support::brevo_mailer(&brevo_url));
+        // The mock has no senders route, so verification is skipped and email is ready
+        mailer.verify_sender().await;
+
+        let mut app = test_app(&dir, false);
+        app.contact = Arc::new(ContactContext { mailer: Some(mailer), ..contact_context(&dir) });
+        let missing = anyhow::anyhow!("Could not read resume.pdf");
+        app.integrity = Arc::new(IntegrityChecks::new(&Err(missing), &Ok(())));
+        let (public, _) = serve_app(&app);
+        let response = reqwest::get(format!("{}/ready", public)).await.unwrap();
+        assert_eq!(response.status(), 200);
+        let status: ReadyStatus = response.json().await.unwrap();
+        assert_eq!((status.checks.assets.as_str(), status.checks.storage.as_str()), ("Could not read resume.pdf", "ok"));
+
+        let unwritable = anyhow::anyhow!("data is not writable");
+        app.integrity = Arc::new(IntegrityChecks::new(&Ok(()), &Err(unwritable)));
+        let (public, _) = serve_app(&app);
+        let response = reqwest::get(format!("{}/ready", public)).await.unwrap();
+        assert_eq!(response.status(), 503);
+        let status: ReadyStatus = response.json().await.unwrap();
+        assert_eq!((status.status.as_str(), status.checks.storage.as_str()), ("unavailable", "data is not writable"));
+    }
+
+    // The contact route behind the same origin check and cors layer as main
+    fn browser_contact() -> impl Filter<Extract = (warp::reply::Response,), Error = warp::Rejection> + Clone {
+        let contact = warp::path!("api" / "contact").and(warp::post()).map(warp::reply);
+        origin_check()
+            .and(contact.with(cors_config()))
+            .map(Reply::into_response)
+            .recover(handle_origin_rejection)
+            .unify()
+    }
+
+    fn preflight(origin: &str) -> warp::test::RequestBuilder {
+        warp::test::request()
+            .method("OPTIONS")
+            .path("/api/contact")
+            .header("origin", origin)
+            .header("referer", format!("{}/contact", origin))
+            .header("access-control-request-method", "POST")
+            .header("access-control-request-headers", "content-type")
+    }
+
+    #[tokio::test]
+    async fn preflight_from_a_disallowed_origin_is_rejected_and_recorded() {
+        let origin = "https://preflight.rejected.example";
+        let seen_before = REJECTED_ORIGINS.lock().unwrap().get(origin).copied();
+        assert_eq!(seen_before, None);
+
+        for _ in 0..2 {
+            let response = preflight(origin).reply(&browser_contact()).await;
+            assert_eq!(response.status(), warp::http::StatusCode::FORBIDDEN);
+            assert!(response.headers().get("access-control-allow-origin").is_none());
+            let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+            assert_eq!(body, serde_json::json!({ "error": "origin not allowed", "origin": origin }));
+        }
+        assert_eq!(REJECTED_ORIGINS.lock().unwrap().get(origin), Some(&2));
+    }
+
+    #[tokio::test]
+    async fn preflight_from_an_allowed_origin_passes() {
+        let response = preflight("https://michaelhenry.me").reply(&browser_contact()).await;
+        assert_eq!(response.status(), warp::http::StatusCode::OK);
+        assert_eq!(response.headers()["access-control-allow-origin"], "https://michaelhenry.me");
+        assert!(!REJECTED_ORIGINS.lock().unwrap().contains_key("https://michaelhenry.me"));
+    }
+
+    // An App with nothing optional configured and ADMIN_API_TOKEN "secret"
+    fn test_app(dir: &TempDir, shared_listener: bool) -> App {
+        App {
+            resume_path: Arc::new(dir.path("resume.pdf")),
+            cache_policy: Arc::new(cache_control::CacheControlPolicy::parse("")),
+            public_stats: Arc::new(stats::PublicStats::parse("", "", "")),
+            public_query_mode: query::QueryMode::Lenient,
+            integrity: Arc::new(IntegrityChecks::new(&Ok(()), &Ok(()))),
+            contact: Arc::new(contact_context(dir)),
+            geoip: None,
+            trusted_proxies: Arc::new(Vec::new()),
+            rate_limiter: None,
+            redirect_table: Arc::new(redirects::RedirectTable::parse("")),
+            error_buffer: error_log::ErrorBuffer::new(10),
+            admin_token: Some(Arc::new("secret".to_string())),
+            faults_allowed: false,
+            base_path: String::new(),
+            shared_listener,
+        }
+    }
+
+    // Serve both listeners of `app` on ephemeral ports: (public, admin) base URLs
+    fn serve_app(app: &App) -> (String, String) {
+        let (public, admin) = routes(app);
+        (test_support::serve(public), test_support::serve(admin))
+    }
+
+    async fn get_status(url: &str, path: &str) -> u16 {
+        reqwest::Client::new()
+            .get(format!("{}{}", url, path))
+            .header("x-admin-token", "secret")
+            .send()
+            .await
+            .unwrap()
+            .status()
+            .as_u16()
+    }
+
+    const ADMIN_PATHS: &[&str] = &[
+        "/api/admin/errors",
+        "/api/admin/redirects",
+        "/api/admin/deprecations",
+        "/api/admin/analytics/variants",
+        "/api/admin/country-policy",
+        "/api/admin/origins",
+        "/api/contact/submissions",
+    ];
+
+    #[tokio::test]
+    async fn admin_routes_are_only_on_the_admin_listener() {
+        let dir = TempDir::new();
+        let (public, admin) = serve_app(&test_app(&dir, false));
+
+        assert_eq!(get_status(&public, "/health").await, 200);
+        assert_eq!(get_status(&admin, "/health").await, 404);
+        for path in ADMIN_PATHS {
+            assert_eq!(get_status(&public, path).await, 404, "{} on the public listener", path);
+            assert_eq!(get_status(&admin, path).await, 200, "{} on the admin listener", path);
+        }
+
+        let without_token = reqwest::get(format!("{}/api/admin/errors", admin)).await.unwrap();
+        assert_eq!(without_token.status().as_u16(), 401);
+    }
+
+    #[tokio::test]
+    async fn shared_listener_serves_admin_routes_publicly() {
+        let dir = TempDir::new();
+        let (public, _) = serve_app(&test_app(&dir, true));
+
+        assert_eq!(get_status(&public, "/health").await, 200);
+        for path in ADMIN_PATHS {
+            assert_eq!(get_status(&public, path).await, 200, "{} on the shared listener", path);
+        }
+    }
+
+    #[cfg(feature = "fault-injection")]
+    #[tokio::test]
+    async fn injected_email_faults_show_through_the_public_api() {
+        let dir = TempDir::new();
+        let (brevo_url, payloads) = test_support::brevo_mock();
+        let mut app = test_app(&dir, false);
+        app.contact = Arc::new(ContactContext {
+            mailer: Some(Arc::new(test_support::brevo_mailer(&brevo_url))),
+            ..contact_context(&dir)
+        });
+        let (_, admin) = serve_app(&app);
+        assert_eq!(get_status(&admin, "/api/admin/faults").await, 404);
+
+        app.faults_allowed = true;
+        let (public, admin) = serve_app(&app);
+        let client = reqwest::Client::new();
+        let faults = format!("{}/api/admin/faults", admin);
+        let contact = || async {
+            let response = client
+                .post(format!("{}/api/v2/contact", public))
+                .json(&serde_json::json!({
+                    "email": "jane@example.com",
+                    "firstName": "Jane",
+                    "lastName": "Doe",
+                    "phoneNumber": "+1 555 0100 200",
+                    "message": "Hello"
+                }))
+                .send()
+                .await
+                .unwrap();
+            response.json::<serde_json::Value>().await.unwrap()
+        };
+
+        let enabled = client
+            .post(&faults)
+            .header("x-admin-token", "secret")
+            .json(&serde_json::json!({ "emailFailurePercent": 100, "ttlSeconds": 60 }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(enabled.status().as_u16(), 200);
+
+        for _ in 0..3 {
+            assert_eq!(contact().await["delivery"], "failed");
+        }
+        assert!(payloads.lock().unwrap().is_empty(), "no email reaches Brevo while the fault is active");
+
+        let failed = client
+            .get(format!("{}/api/contact/submissions?status=failed", admin))
+            .header("x-admin-token", "secret")
+            .send()
+            .await
+            .unwrap()
+            .json::<serde_json::Value>()
+            .await
+            .unwrap();
+        assert_eq!(failed["submissions"].as_array().unwrap().len(), 3);
+        assert_eq!(failed["submissions"][0]["lastError"], "Injected email fault");
+
+        let cleared = client.delete(&faults).header("x-admin-token", "secret").send().await.unwrap();
+        assert_eq!(cleared.status().as_u16(), 204);
+        assert_eq!(contact().await["delivery"], "sent");
+        assert_eq!(payloads.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn base_path_is_normalized() {
+        assert_eq!(normalize_base_path(""), "");
+        assert_eq!(normalize_base_path("/"), "");
+        assert_eq!(normalize_base_path("svc"), "/svc");
+        assert_eq!(normalize_base_path("/svc/"), "/svc");
+        assert_eq!(normalize_base_path(" //personal/ api// "), "/personal/api");
+    }
+
+    // Serve `routes` with an access log that records each line it writes
+    fn serve_logged(routes: Routes, base_path: &str) -> (String, Arc<Mutex<Vec<String>>>) {
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let seen = lines.clone();
+        let base_path = base_path.to_string();
+        let logged = routes.with(warp::log::custom(move |info| {
+            seen.lock().unwrap().push(access_log_line(&info, &base_path));
+        }));
+        (test_support::serve(logged), lines)
+    }
+
+    #[tokio::test]
+    async fn routes_live_under_the_base_path() {
+        let dir = TempDir::new();
+        let app = App { base_path: "/svc".to_string(), ..test_app(&dir, false) };
+        let (public, admin) = routes(&app);
+        let (public, public_log) = serve_logged(public, &app.base_path);
+        let (admin, admin_log) = serve_logged(admin, &app.base_path);
+
+        assert_eq!(get_status(&public, "/svc/health").await, 200);
+        assert_eq!(get_status(&public, "/svc/api/deprecations").await, 200);
+        assert_eq!(get_status(&public, "/svc/api/stats/public").await, 200);
+        assert_eq!(get_status(&public, "/health").await, 404);
+        assert_eq!(get_status(&public, "/svcx/health").await, 404);
+        assert_eq!(get_status(&admin, "/svc/api/admin/errors").await, 200);
+        assert_eq!(get_status(&admin, "/api/admin/errors").await, 404);
+
+        // Log lines show the route without BASE_PATH; paths outside it are shown as is
+        let public_log = public_log.lock().unwrap();
+        assert!(public_log[0].contains("\"GET /health HTTP/1.1\" 200"), "{}", public_log[0]);
+        assert!(public_log[1].contains("\"GET /api/deprecations HTTP/1.1\" 200"), "{}", public_log[1]);
+        assert!(public_log[3].contains("\"GET /health HTTP/1.1\" 404"), "{}", public_log[3]);
+        assert!(public_log[4].contains("\"GET /svcx/health HTTP/1.1\" 404"), "{}", public_log[4]);
+        assert!(admin_log.lock().unwrap()[0].contains("\"GET /api/admin/errors HTTP/1.1\" 200"));
+    }
+
+    #[tokio::test]
+    async fn routes_without_a_base_path() {
+        let dir = TempDir::new();
+        let app = test_app(&dir, false);
+        let (public, _) = routes(&app);
+        let (public, log) = serve_logged(public, &app.base_path);
+
+        assert_eq!(get_status(&public, "/health").await, 200);
+        assert_eq!(get_status(&public, "/svc/health").await, 404);
+        assert_eq!(get_status(&public, "/").await, 404);
+
+        let log = log.lock().unwrap();
+        assert!(log[0].contains("\"GET /health HTTP/1.1\" 200"), "{}", log[0]);
+        assert!(log[2].contains("\"GET / HTTP/1.1\" 404"), "{}", log[2]);
+    }
+
+    fn variant_form(variant: &str) -> serde_json::Value {
+        serde_json::json!({
+            "email": "jane@example.com",
+            "firstName": "Jane",
+            "lastName": "Doe",
+            "phoneNumber": "+1 555 0100 200",
+            "message": "Hello",
+            "variant": variant
+        })
+    }
+
+    #[test]
+    fn form_variants_are_a_comma_separated_list() {
+        assert_eq!(parse_form_variants(" short, long ,,"), ["short", "long"]);
+        assert!(parse_form_variants("").is_empty());
+    }
+
+    #[tokio::test]
+    async fn only_allowlisted_variants_are_accepted() {
+        let dir = TempDir::new();
+        let context = Arc::new(ContactContext {
+            form_variants: parse_form_variants("short,long"),
+            ..contact_context(&dir)
+        });
+
+        let (response, body) = submit(&context, variant_form("tall")).await;
+        assert_eq!(response.status(), warp::http::StatusCode::BAD_REQUEST);
+        assert_eq!(body["errors"]["variant"], "Unknown form variant 'tall'");
+        assert!(stored(&context).await.is_empty());
+
+        submit(&context, variant_form("short")).await;
+        submit(&context, variant_form("  ")).await;
+        let variants: Vec<_> = stored(&context).await.into_iter().map(|s| s.variant).collect();
+        assert_eq!(variants.len(), 2);
+        assert!(variants.contains(&Some("short".to_string())));
+        assert!(variants.contains(&None));
+
+        // Without CONTACT_FORM_VARIANTS, any variant is refused
+        let context = Arc::new(contact_context(&dir));
+        let (response, _) = submit(&context, variant_form("short")).await;
+        assert_eq!(response.status(), warp::http::StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn submissions_are_counted_per_variant() {
+        let dir = TempDir::new();
+        let mut app = test_app(&dir, false);
+        app.contact = Arc::new(ContactContext {
+            form_variants: parse_form_variants("counted-a,counted-b"),
+            ..contact_context(&dir)
+        });
+
+        for variant in ["counted-a", "counted-a", "counted-b", "counted-c"] {
+            submit(&app.contact, variant_form(variant)).await;
+        }
+        let counts = stats::contacts_by_variant();
+        assert_eq!((counts.get("counted-a"), counts.get("counted-b")), (Some(&2), Some(&1)));
+        assert_eq!(counts.get("counted-c"), None);
+
+        let (_, admin) = serve_app(&app);
+        let body: serde_json::Value = reqwest::Client::new()
+            .get(format!("{}/api/admin/analytics/variants", admin))
+            .header("x-admin-token", "secret")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(body["submissions"]["counted-a"], 2);
+        assert_eq!(body["submissions"]["counted-b"], 1);
+    }
+
+    #[tokio::test]
+    async fn blocked_countries_are_refused_and_counted() {
+        let dir = TempDir::new();
+        let mut app = test_app(&dir, false);
+        // No GeoIP database in tests, so every submission falls to the '*' rule
+        app.contact = Arc::new(ContactContext {
+            country_policy: Arc::new(country_policy::CountryPolicy::parse("CN=block,*=block")),
+            ..contact_context(&dir)
+        });
+
+        let (response, body) = submit(&app.contact, legacy_form()).await;
+        assert_eq!(response.status(), warp::http::StatusCode::FORBIDDEN);
+        assert_eq!(body["success"], false);
+        assert!(stored(&app.contact).await.is_empty());
+
+        let (_, admin) = serve_app(&app);
+        let body: serde_json::Value = reqwest::Client::new()
+            .get(format!("{}/api/admin/country-policy", admin))
+            .header("x-admin-token", "secret")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!([
+                { "country": "CN", "action": "block", "hits": 0 },
+                { "country": "*", "action": "block", "hits": 1 },
+            ])
+        );
+    }
+
+    fn client_form(email: &str) -> ContactForm {
+        ContactForm {
+            email: email.to_string(),
+            first_name: "Jane".to_string(),
+            last_name: "Doe".to_string(),
+            phone_number: "+1 555 0100 200".to_string(),
+            message: "Hello".to_string(),
+            ..ContactForm::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn client_round_trips_with_the_routes() {
+        use personal_api::client::{ClientError, PersonalApiClient};
+        use personal_api::models::SubmissionStatus;
+
+        let dir = TempDir::new();
+        let (brevo_url, payloads) = test_support::brevo_mock();
+        let mut app = test_app(&dir, false);
+        app.contact = Arc::new(ContactContext {
+            mailer: Some(Arc::new(test_support::brevo_mailer(&brevo_url))),
+            ..contact_context(&dir)
+        });
+        let (public, admin) = serve_app(&app);
+        let public = PersonalApiClient::new(format!("{}/", public), None);
+        let admin = PersonalApiClient::new(admin, Some("secret".to_string()));
+
+        // Sender verification hasn't run, so the 503 body comes back as Ok
+        let status = public.get_status().await.unwrap();
+        assert_eq!((status.status.as_str(), status.checks.email.as_str()), ("unavailable", "sender verification pending"));
+
+        let sent = public.submit_contact(&client_form("jane@example.com")).await.unwrap();
+        assert_eq!(sent.delivery, DeliveryStatus::Sent);
+        assert_eq!(payloads.lock().unwrap().len(), 1);
+
+        let list = admin.list_submissions(Some(SubmissionStatus::Sent)).await.unwrap();
+        assert_eq!((list.count, list.submissions[0].id.as_str()), (1, sent.id.as_str()));
+        assert!(admin.list_submissions(Some(SubmissionStatus::Failed)).await.unwrap().submissions.is_empty());
+        let submission = admin.get_submission(&sent.id).await.unwrap();
+        assert_eq!((submission.email.as_str(), submission.attempts), ("jane@example.com", 1));
+
+        let retried = admin.retry_failed().await.unwrap();
+        assert_eq!((retried.attempted, retried.sent, retried.failed), (0, 0, 0));
+        admin.list_errors(Some("/api/contact")).await.unwrap();
+        admin.clear_errors().await.unwrap();
+
+        // The id is a single path segment, so this can't reach another route
+        match admin.get_submission("../../admin/errors").await {
+            Err(ClientError::Api { status, message, .. }) => assert_eq!((status, message.as_str()), (404, "submission not found")),
+            other => panic!("expected a 404, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn client_surfaces_api_errors() {
+        use personal_api::client::{ClientError, PersonalApiClient};
+
+        let dir = TempDir::new();
+        let (public, admin) = serve_app(&test_app(&dir, false));
+
+        match PersonalApiClient::new(&public, None).submit_contact(&client_form("not-an-email")).await {
+            Err(ClientError::Api { status, message, details }) => {
+                assert_eq!((status, message.as_str()), (400, "Validation failed"));
+                assert!(details.unwrap().get("email").is_some());
+            }
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+
+        match PersonalApiClient::new(&admin, Some("wrong".to_string())).list_errors(None).await {
+            Err(ClientError::Api { status, message, details }) => {
+                assert_eq!((status, message.as_str(), details), (401, "unauthorized", None));
+            }
+            other => panic!("expected a 401, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_emails_are_accepted_and_resent_from_the_admin_routes() {
+        let dir = TempDir::new();
+        let client = reqwest::Client::new();
+        // No mailer: the row is stored and its email fails
+        let (public, admin) = serve_app(&test_app(&dir, false));
+
+        let response = client
+            .post(format!("{}/api/v2/contact", public))
+            .json(&client_form("jane@example.com"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 200);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!((body["success"].clone(), body["delivery"].clone()), (true.into(), "failed".into()));
+        assert!(body.get("retry").is_none());
+        let id = body["id"].as_str().unwrap().to_string();
+
+        let admin_post = |url: &str, path: &str| client.post(format!("{}{}", url, path)).header("x-admin-token", "secret").send();
+        let unconfigured = admin_post(&admin, "/api/contact/retry-failed").await.unwrap();
+        assert_eq!(unconfigured.status().as_u16(), 503);
+
+        // Same database, now with a working mailer
+        let (brevo_url, payloads) = test_support::brevo_mock();
+        let mut app = test_app(&dir, false);
+        app.contact = Arc::new(ContactContext {
+            mailer: Some(Arc::new(test_support::brevo_mailer(&brevo_url))),
+            ..contact_context(&dir)
+        });
+        let (_, admin) = serve_app(&app);
+        let summary: serde_json::Value = admin_post(&admin, "/api/contact/retry-failed").await.unwrap().json().await.unwrap();
+        assert_eq!(summary, serde_json::json!({ "attempted": 1, "sent": 1, "failed": 0 }));
+        assert_eq!(payloads.lock().unwrap().len(), 1);
+
+        let submission: serde_json::Value = client
+            .get(format!("{}/api/contact/submissions/{}", admin, id))
+            .header("x-admin-token", "secret")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!((submission["status"].clone(), submission["attempts"].clone()), ("sent".into(), 2.into()));
+    }
+
+    fn rate_limited_app(dir: &TempDir, limit: u32) -> App {
+        App {
+            rate_limiter: rate_limit::RateLimiter::new(limit, std::time::Duration::from_secs(600)).map(Arc::new),
+            trusted_proxies: Arc::new(vec!["127.0.0.1".parse().unwrap()]),
+            ..test_app(dir, false)
+        }
+    }
+
+    async fn post_contact(public: &str, client_ip: &str, form: &impl serde::Serialize) -> reqwest::Response {
+        reqwest::Client::new()
+            .post(format!("{}/api/contact", public))
+            .header("x-forwarded-for", client_ip)
+            .json(form)
+            .send()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn submissions_over_the_limit_get_429_with_retry_after() {
+        let dir = TempDir::new();
+        let app = rate_limited_app(&dir, 2);
+        let (public, _) = serve_app(&app);
+
+        for _ in 0..2 {
+            let response = post_contact(&public, "198.51.100.1", &client_form("jane@example.com")).await;
+            assert_eq!(response.status().as_u16(), 200);
+        }
+
+        let limited = post_contact(&public, "198.51.100.1", &client_form("jane@example.com")).await;
+        assert_eq!(limited.status().as_u16(), 429);
+        assert_eq!(limited.headers()["retry-after"], "300");
+        let body: serde_json::Value = limited.json().await.unwrap();
+        assert_eq!((body["code"].clone(), body["retryAfterSeconds"].clone()), ("RATE_LIMITED".into(), 300.into()));
+        assert_eq!(stored(&app.contact).await.len(), 2);
+
+        // Another client behind the same proxy still gets through
+        let other = post_contact(&public, "198.51.100.2", &client_form("jane@example.com")).await;
+        assert_eq!(other.status().as_u16(), 200);
+    }
+
+    #[tokio::test]
+    async fn honeypot_submissions_look_accepted_but_go_nowhere() {
+        let dir = TempDir::new();
+        let (brevo_url, payloads) = test_support::brevo_mock();
+        let mut app = rate_limited_app(&dir, 5);
+        app.contact = Arc::new(ContactContext {
+            mailer: Some(Arc::new(test_support::brevo_mailer(&brevo_url))),
+            ..contact_context(&dir)
+        });
+        let (public, _) = serve_app(&app);
+
+        let form = ContactForm {
+            website: Some("https://spam.example".to_string()),
+            ..client_form("bot@example.com")
+        };
+        let response = post_contact(&public, "198.51.100.1", &form).await;
+        assert_eq!(response.status().as_u16(), 200);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!((body["success"].clone(), body["message"].clone()), (true.into(), CONTACT_SENT_MESSAGE.into()));
+        assert!(stored(&app.contact).await.is_empty());
+        assert!(payloads.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn resume_download_is_only_at_its_own_path() {
+        let dir = TempDir::new();
+        let app = test_app(&dir, false);
+        fs::write(app.resume_path.as_str(), b"%PDF-1.4 not much of a resume").unwrap();
+        let (public, _) = serve_app(&app);
+
+        assert_eq!(get_status(&public, "/api/resume").await, 200);
+        assert_eq!(get_status(&public, "/api/resume/meta").await, 200);
+        assert_eq!(get_status(&public, "/api/resume/anything").await, 404);
+        assert_eq!(get_status(&public, "/api/resume/resume.pdf").await, 404);
+    }
 }
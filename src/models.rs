@@ -0,0 +1,292 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::sanitize_input;
+
+// Longest value kept for any attribution field
+pub const MAX_ATTRIBUTION_FIELD_LEN: usize = 200;
+
+// Media type clients send in Accept to opt into the v2 contact response
+pub const CONTACT_V2_MEDIA_TYPE: &str = "application/vnd.personal-api.v2+json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate)]
+pub struct ContactForm {
+    #[validate(email)]
+    pub email: String,
+    #[validate(length(min = 1, max = 100))]
+    #[serde(rename = "firstName", alias = "first_name")]
+    pub first_name: String,
+    #[validate(length(min = 1, max = 100))]
+    #[serde(rename = "lastName", alias = "last_name")]
+    pub last_name: String,
+    #[validate(length(min = 10, max = 20))]
+    #[serde(rename = "phoneNumber", alias = "phone_number")]
+    pub phone_number: String,
+    #[validate(length(min = 1, max = 1000))]
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attribution: Option<Attribution>,
+    // Which form design was used, one of the server's CONTACT_FORM_VARIANTS
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+    // Honeypot, hidden from real users; a filled-in value marks the submission as spam
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub website: Option<String>,
+    // Looked up by the server from the client IP, never taken from the body
+    #[serde(skip)]
+    pub location: Option<GeoLocation>,
+}
+
+// Where a submission came from, as reported by the frontend
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Attribution {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub referrer: Option<String>,
+    #[serde(alias = "utm_source", skip_serializing_if = "Option::is_none")]
+    pub utm_source: Option<String>,
+    #[serde(alias = "utm_medium", skip_serializing_if = "Option::is_none")]
+    pub utm_medium: Option<String>,
+    #[serde(alias = "utm_campaign", skip_serializing_if = "Option::is_none")]
+    pub utm_campaign: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub landing_page: Option<String>,
+}
+
+impl Attribution {
+    // Sanitize and truncate every field, dropping the ones left empty
+    pub fn truncated(self) -> Self {
+        let clean = |value: Option<String>| {
+            value
+                .map(|v| sanitize_input(&v).chars().take(MAX_ATTRIBUTION_FIELD_LEN).collect::<String>())
+                .filter(|v| !v.is_empty())
+        };
+
+        Attribution {
+            referrer: clean(self.referrer),
+            utm_source: clean(self.utm_source),
+            utm_medium: clean(self.utm_medium),
+            utm_campaign: clean(self.utm_campaign),
+            landing_page: clean(self.landing_page),
+        }
+    }
+
+    // One-line summary for the notification email
+    pub fn summary(&self) -> String {
+        [
+            ("source", &self.utm_source),
+            ("medium", &self.utm_medium),
+            ("campaign", &self.utm_campaign),
+            ("referrer", &self.referrer),
+            ("landing page", &self.landing_page),
+        ]
+        .iter()
+        .filter_map(|(label, value)| value.as_ref().map(|v| format!("{}: {}", label, v)))
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+}
+
+// Where a submission came from, by client IP
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeoLocation {
+    #[serde(rename = "countryCode")]
+    pub country_code: Option<String>,
+    pub city: Option<String>,
+}
+
+impl GeoLocation {
+    // "Berlin, DE" for the notification email
+    pub fn summary(&self) -> Option<String> {
+        match (&self.city, &self.country_code) {
+            (Some(city), Some(country)) => Some(format!("{}, {}", city, country)),
+            (None, Some(country)) => Some(country.clone()),
+            (Some(city), None) => Some(city.clone()),
+            (None, None) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    Sent,
+    Failed,
+}
+
+// POST /api/contact and /api/v1/contact
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactResponse {
+    pub success: bool,
+    pub message: String,
+    pub id: String,
+}
+
+// POST /api/v2/contact, or /api/contact with the v2 media type in Accept
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactResponseV2 {
+    pub success: bool,
+    pub message: String,
+    pub id: String,
+    #[serde(rename = "requestId")]
+    pub request_id: String,
+    pub delivery: DeliveryStatus,
+    #[serde(rename = "receivedAt")]
+    pub received_at: String,
+}
+
+// Error body of the contact routes: validation failures, bad JSON, blocked
+// submissions. `errors` carries per-field details when there are any.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactError {
+    pub success: bool,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub errors: Option<serde_json::Value>,
+    // Set on 429, matching the Retry-After header
+    #[serde(rename = "retryAfterSeconds", default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_seconds: Option<u64>,
+}
+
+impl ContactError {
+    pub fn new(message: impl Into<String>) -> Self {
+        ContactError {
+            success: false,
+            message: message.into(),
+            code: None,
+            errors: None,
+            retry_after_seconds: None,
+        }
+    }
+
+    pub fn with_code(mut self, code: &str) -> Self {
+        self.code = Some(code.to_string());
+        self
+    }
+
+    pub fn with_errors(mut self, errors: impl Serialize) -> Self {
+        self.errors = serde_json::to_value(errors).ok();
+        self
+    }
+
+    pub fn with_retry_after(mut self, seconds: u64) -> Self {
+        self.retry_after_seconds = Some(seconds);
+        self
+    }
+}
+
+// Error body of every other route, e.g. {"error": "unauthorized"}. Some
+// add context next to `error`, e.g. the offending origin.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorMessage {
+    pub error: String,
+    #[serde(flatten)]
+    pub details: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ErrorMessage {
+    pub fn new(error: impl Into<String>) -> Self {
+        ErrorMessage {
+            error: error.into(),
+            details: serde_json::Map::new(),
+        }
+    }
+
+    pub fn with_detail(mut self, key: &str, value: impl Serialize) -> Self {
+        if let Ok(value) = serde_json::to_value(value) {
+            self.details.insert(key.to_string(), value);
+        }
+        self
+    }
+}
+
+// GET /health
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthStatus {
+    pub status: String,
+}
+
+// GET /ready; served with 503 when `status` is "unavailable"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadyStatus {
+    pub status: String,
+    pub checks: ReadyChecks,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadyChecks {
+    pub email: String,
+    // Results of the startup integrity pass
+    pub assets: String,
+    pub storage: String,
+}
+
+// A recent error-level event and the request it happened in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorEntry {
+    pub timestamp: String,
+    #[serde(rename = "requestId")]
+    pub request_id: Option<String>,
+    pub route: Option<String>,
+    pub target: String,
+    pub message: String,
+}
+
+// GET /api/admin/errors
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorList {
+    pub count: usize,
+    pub errors: Vec<ErrorEntry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubmissionStatus {
+    // Stored, email not sent yet
+    Pending,
+    Sent,
+    Failed,
+}
+
+// A stored contact submission, with its fields as sanitized on receipt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Submission {
+    pub id: String,
+    #[serde(rename = "requestId")]
+    pub request_id: String,
+    #[serde(rename = "receivedAt")]
+    pub received_at: String,
+    pub email: String,
+    #[serde(rename = "firstName")]
+    pub first_name: String,
+    #[serde(rename = "lastName")]
+    pub last_name: String,
+    #[serde(rename = "phoneNumber")]
+    pub phone_number: String,
+    pub message: String,
+    pub attribution: Option<Attribution>,
+    pub variant: Option<String>,
+    pub location: Option<GeoLocation>,
+    pub status: SubmissionStatus,
+    // Email attempts so far, including the one made on receipt
+    pub attempts: u32,
+    #[serde(rename = "lastError")]
+    pub last_error: Option<String>,
+}
+
+// GET /api/contact/submissions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmissionList {
+    pub count: usize,
+    pub submissions: Vec<Submission>,
+}
+
+// POST /api/contact/retry-failed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetrySummary {
+    pub attempted: usize,
+    pub sent: usize,
+    pub failed: usize,
+}
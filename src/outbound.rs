@@ -0,0 +1,170 @@
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use reqwest::Client;
+use std::env;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use warp::hyper::client::connect::dns::Name;
+
+// Which address family outbound connections try first. Hyper's happy
+// eyeballs connector attempts the family of the first resolved address and
+// falls back to the other, so ordering the DNS answer is enough.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AddressFamilyPolicy {
+    System,
+    PreferIpv4,
+    PreferIpv6,
+}
+
+impl AddressFamilyPolicy {
+    fn from_env() -> Self {
+        match env::var("OUTBOUND_IP_POLICY").as_deref() {
+            Ok("prefer-ipv4") => AddressFamilyPolicy::PreferIpv4,
+            Ok("prefer-ipv6") => AddressFamilyPolicy::PreferIpv6,
+            Ok("system") | Err(_) => AddressFamilyPolicy::System,
+            Ok(other) => {
+                tracing::warn!("Unknown OUTBOUND_IP_POLICY '{}', using system", other);
+                AddressFamilyPolicy::System
+            }
+        }
+    }
+
+    // Stable reorder so the preferred family comes first
+    fn order(self, mut addrs: Vec<SocketAddr>) -> Vec<SocketAddr> {
+        match self {
+            AddressFamilyPolicy::System => {}
+            AddressFamilyPolicy::PreferIpv4 => addrs.sort_by_key(|addr| !addr.is_ipv4()),
+            AddressFamilyPolicy::PreferIpv6 => addrs.sort_by_key(|addr| !addr.is_ipv6()),
+        }
+        addrs
+    }
+}
+
+// System DNS lookup with the answer reordered per AddressFamilyPolicy
+struct PolicyResolver {
+    policy: AddressFamilyPolicy,
+}
+
+impl Resolve for PolicyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let policy = self.policy;
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs = tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            Ok(Box::new(policy.order(addrs).into_iter()) as Addrs)
+        })
+    }
+}
+
+// Parse OUTBOUND_DNS_OVERRIDES: "api.brevo.com=1.2.3.4,example.com=[2001:db8::1]"
+fn parse_dns_overrides(config: &str) -> Vec<(String, SocketAddr)> {
+    config
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| {
+            let parsed = entry.split_once('=').and_then(|(host, ip)| {
+                let ip = ip.trim().trim_start_matches('[').trim_end_matches(']');
+                ip.parse::<IpAddr>()
+                    .ok()
+                    .map(|ip| (host.trim().to_lowercase(), SocketAddr::new(ip, 0)))
+            });
+            if parsed.is_none() {
+                tracing::warn!("Ignoring invalid OUTBOUND_DNS_OVERRIDES entry: {}", entry);
+            }
+            parsed
+        })
+        .collect()
+}
+
+fn duration_from_env(var: &str, default_secs: u64) -> Duration {
+    let secs = match env::var(var) {
+        Ok(value) => value.trim().parse::<u64>().unwrap_or_else(|_| {
+            tracing::warn!("Invalid {} '{}', using {}", var, value, default_secs);
+            default_secs
+        }),
+        Err(_) => default_secs,
+    };
+    Duration::from_secs(secs)
+}
+
+// The HTTP client shared by every outbound call (Brevo, translation)
+pub fn client_from_env() -> Result<Client, anyhow::Error> {
+    let policy = AddressFamilyPolicy::from_env();
+
+    let mut builder = Client::builder()
+        .connect_timeout(duration_from_env("OUTBOUND_CONNECT_TIMEOUT_SECS", 5))
+        .timeout(duration_from_env("OUTBOUND_TIMEOUT_SECS", 30));
+
+    if policy != AddressFamilyPolicy::System {
+        builder = builder.dns_resolver(Arc::new(PolicyResolver { policy }));
+    }
+
+    for (host, addr) in parse_dns_overrides(&env::var("OUTBOUND_DNS_OVERRIDES").unwrap_or_default()) {
+        tracing::info!("Outbound DNS override: {} -> {}", host, addr.ip());
+        builder = builder.resolve(&host, addr);
+    }
+
+    tracing::debug!("Outbound address family policy: {:?}", policy);
+    Ok(builder.build()?)
+}
+
+// "IPv4 1.2.3.4:443" for logging which address an outbound call used
+pub fn describe_remote(addr: Option<SocketAddr>) -> String {
+    match addr {
+        Some(addr) if addr.is_ipv4() => format!("IPv4 {}", addr),
+        Some(addr) => format!("IPv6 {}", addr),
+        None => "unknown address".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(s: &str) -> SocketAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn dns_overrides_parse_ipv4_and_bracketed_ipv6() {
+        let overrides = parse_dns_overrides(" API.Brevo.com = 1.2.3.4 ,example.com=[2001:db8::1],plain.test=2001:db8::2");
+        assert_eq!(
+            overrides,
+            vec![
+                ("api.brevo.com".to_string(), addr("1.2.3.4:0")),
+                ("example.com".to_string(), addr("[2001:db8::1]:0")),
+                ("plain.test".to_string(), addr("[2001:db8::2]:0")),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_dns_overrides_are_skipped() {
+        let overrides = parse_dns_overrides("no-equals-sign,bad.test=not-an-ip,,ok.test=10.0.0.1,port.test=10.0.0.2:443");
+        assert_eq!(overrides, vec![("ok.test".to_string(), addr("10.0.0.1:0"))]);
+        assert!(parse_dns_overrides("").is_empty());
+    }
+
+    #[test]
+    fn preferred_family_comes_first_in_a_stable_order() {
+        let answer = vec![addr("[2001:db8::1]:443"), addr("1.1.1.1:443"), addr("[2001:db8::2]:443"), addr("2.2.2.2:443")];
+
+        assert_eq!(AddressFamilyPolicy::System.order(answer.clone()), answer);
+        assert_eq!(
+            AddressFamilyPolicy::PreferIpv4.order(answer.clone()),
+            vec![addr("1.1.1.1:443"), addr("2.2.2.2:443"), addr("[2001:db8::1]:443"), addr("[2001:db8::2]:443")]
+        );
+        assert_eq!(
+            AddressFamilyPolicy::PreferIpv6.order(answer),
+            vec![addr("[2001:db8::1]:443"), addr("[2001:db8::2]:443"), addr("1.1.1.1:443"), addr("2.2.2.2:443")]
+        );
+    }
+
+    #[test]
+    fn single_family_answers_are_unchanged() {
+        let ipv4_only = vec![addr("1.1.1.1:443"), addr("2.2.2.2:443")];
+        assert_eq!(AddressFamilyPolicy::PreferIpv6.order(ipv4_only.clone()), ipv4_only);
+        assert!(AddressFamilyPolicy::PreferIpv4.order(Vec::new()).is_empty());
+    }
+}
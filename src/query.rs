@@ -0,0 +1,285 @@
+use personal_api::models::ErrorMessage;
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+use std::env;
+use warp::http::header::{HeaderValue, WARNING};
+use warp::Filter;
+
+// What a route does with unknown parameters and values that don't parse
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QueryMode {
+    // 400 listing the valid parameters
+    Strict,
+    // Drop them, log it and answer with a Warning header
+    Lenient,
+}
+
+impl QueryMode {
+    // Format: PUBLIC_QUERY_VALIDATION=strict|lenient (default: lenient).
+    // Admin routes are always strict.
+    pub fn from_env() -> Self {
+        Self::parse(&env::var("PUBLIC_QUERY_VALIDATION").unwrap_or_default())
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "" | "lenient" => QueryMode::Lenient,
+            "strict" => QueryMode::Strict,
+            other => {
+                tracing::warn!("Invalid PUBLIC_QUERY_VALIDATION '{}', using lenient", other);
+                QueryMode::Lenient
+            }
+        }
+    }
+}
+
+// A query struct and the parameters it accepts. Query structs also use
+// deny_unknown_fields, so a name listed here but missing from the struct
+// fails to decode instead of passing silently.
+pub trait QueryParams: DeserializeOwned {
+    // (name, expected value) for each parameter, shown in the 400 body
+    const PARAMS: &'static [(&'static str, &'static str)];
+}
+
+// A query string checked against `T::PARAMS`. In lenient mode it also
+// carries what was dropped, for the Warning header.
+#[derive(Debug)]
+pub struct ValidatedQuery<T> {
+    params: T,
+    warning: Option<String>,
+}
+
+impl<T> ValidatedQuery<T> {
+    pub fn into_inner(self) -> T {
+        self.params
+    }
+
+    // `reply`, with a Warning header when parameters were dropped
+    pub fn reply(&self, reply: impl warp::Reply) -> warp::reply::Response {
+        let mut response = reply.into_response();
+        if let Some(warning) = &self.warning {
+            if let Ok(value) = HeaderValue::from_str(&format!("299 - \"{}\"", warning.escape_default())) {
+                response.headers_mut().insert(WARNING, value);
+            }
+        }
+        response
+    }
+}
+
+// Rejection for a query with unknown parameters or values that don't parse
+#[derive(Debug)]
+pub struct InvalidQuery {
+    message: String,
+    params: &'static [(&'static str, &'static str)],
+}
+
+impl warp::reject::Reject for InvalidQuery {}
+
+impl InvalidQuery {
+    // 400 body naming the problem and the parameters the endpoint accepts
+    pub fn body(&self) -> ErrorMessage {
+        let valid: BTreeMap<_, _> = self.params.iter().copied().collect();
+        ErrorMessage::new(self.message.clone()).with_detail("validParameters", valid)
+    }
+}
+
+// The query string as `T`, accepting only the parameters in `T::PARAMS`
+pub fn validated<T>(mode: QueryMode) -> impl Filter<Extract = (ValidatedQuery<T>,), Error = warp::Rejection> + Clone
+where
+    T: QueryParams + Send + 'static,
+{
+    warp::path::full()
+        .and(warp::query::raw().or(warp::any().map(String::new)).unify())
+        .and_then(move |path: warp::path::FullPath, raw: String| async move {
+            let query = validate::<T>(&raw, mode).map_err(warp::reject::custom)?;
+            if let Some(warning) = &query.warning {
+                tracing::warn!("Ignored query parameters on {}: {}", path.as_str(), personal_api::sanitize_input(warning));
+            }
+            Ok::<_, warp::Rejection>(query)
+        })
+}
+
+// Like warp::query, but unknown parameter names are an error rather than ignored
+pub fn strict<T>() -> impl Filter<Extract = (T,), Error = warp::Rejection> + Clone
+where
+    T: QueryParams + Send + 'static,
+{
+    validated::<T>(QueryMode::Strict).map(ValidatedQuery::into_inner)
+}
+
+fn validate<T: QueryParams>(raw: &str, mode: QueryMode) -> Result<ValidatedQuery<T>, InvalidQuery> {
+    let params = T::PARAMS;
+    let invalid = |message: String| InvalidQuery { message, params };
+    let mut problems: Vec<String> = Vec::new();
+
+    let pairs: Vec<(String, String)> = match serde_urlencoded::from_str(raw) {
+        Ok(pairs) => pairs,
+        Err(e) if mode == QueryMode::Strict => return Err(invalid(format!("malformed query string: {}", e))),
+        Err(e) => {
+            problems.push(format!("malformed query string: {}", e));
+            Vec::new()
+        }
+    };
+
+    let (known, unknown): (Vec<_>, Vec<_>) =
+        pairs.into_iter().partition(|(name, _)| params.iter().any(|(valid, _)| valid == name));
+    if !unknown.is_empty() {
+        let names: Vec<&str> = unknown.iter().map(|(name, _)| name.as_str()).collect();
+        let message = format!("unknown query parameter: {}", names.join(", "));
+        if mode == QueryMode::Strict {
+            return Err(invalid(message));
+        }
+        problems.push(message);
+    }
+
+    let params = match (decode::<T>(&known), mode) {
+        (Ok(params), _) => params,
+        (Err(e), QueryMode::Strict) => return Err(invalid(format!("invalid query parameter: {}", e))),
+        // Keep each value that still parses alongside the ones kept before it
+        (Err(_), QueryMode::Lenient) => {
+            let mut kept = Vec::new();
+            for pair in known {
+                kept.push(pair);
+                if let Err(e) = decode::<T>(&kept) {
+                    let (name, _) = kept.pop().unwrap_or_default();
+                    problems.push(format!("invalid value for {}: {}", name, e));
+                }
+            }
+            decode::<T>(&kept).map_err(|e| invalid(format!("invalid query parameter: {}", e)))?
+        }
+    };
+
+    Ok(ValidatedQuery {
+        params,
+        warning: (!problems.is_empty()).then(|| problems.join("; ")),
+    })
+}
+
+fn decode<T: DeserializeOwned>(pairs: &[(String, String)]) -> Result<T, serde_urlencoded::de::Error> {
+    let raw = serde_urlencoded::to_string(pairs).unwrap_or_default();
+    serde_urlencoded::from_str(&raw)
+}
+
+// For routes that take no parameters at all
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NoParams {}
+
+impl QueryParams for NoParams {
+    const PARAMS: &'static [(&'static str, &'static str)] = &[];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use personal_api::models::SubmissionStatus;
+    use serde::Deserialize;
+    use warp::http::StatusCode;
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Filters {
+        status: Option<SubmissionStatus>,
+        limit: Option<u32>,
+        #[serde(rename = "q")]
+        search: Option<String>,
+    }
+
+    impl QueryParams for Filters {
+        const PARAMS: &'static [(&'static str, &'static str)] =
+            &[("status", "one of pending, sent, failed"), ("limit", "integer"), ("q", "string")];
+    }
+
+    async fn get(mode: QueryMode, query: &str) -> warp::http::Response<warp::hyper::body::Bytes> {
+        let route = validated::<Filters>(mode)
+            .map(|query: ValidatedQuery<Filters>| {
+                let body = format!("{:?} {:?}", query.params.status, query.params.limit);
+                query.reply(body)
+            })
+            .recover(crate::admin::handle_rejection);
+        warp::test::request().path(&format!("/list{}", query)).reply(&route).await
+    }
+
+    fn json(response: &warp::http::Response<warp::hyper::body::Bytes>) -> serde_json::Value {
+        serde_json::from_slice(response.body()).unwrap()
+    }
+
+    #[test]
+    fn a_listed_parameter_the_struct_lacks_fails_to_decode() {
+        #[derive(Debug, Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Drifted {}
+
+        impl QueryParams for Drifted {
+            const PARAMS: &'static [(&'static str, &'static str)] = &[("removed", "string")];
+        }
+
+        let error = validate::<Drifted>("removed=1", QueryMode::Strict).unwrap_err();
+        assert_eq!(error.message, "invalid query parameter: unknown field `removed`, there are no fields");
+    }
+
+    #[tokio::test]
+    async fn known_parameters_parse() {
+        let response = get(QueryMode::Strict, "?status=sent&limit=5").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.body(), "Some(Sent) Some(5)");
+        assert!(response.headers().get(WARNING).is_none());
+    }
+
+    #[tokio::test]
+    async fn unknown_keys_are_rejected_in_strict_mode() {
+        let response = get(QueryMode::Strict, "?stauts=sent&limit=5").await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            json(&response),
+            serde_json::json!({
+                "error": "unknown query parameter: stauts",
+                "validParameters": {
+                    "status": "one of pending, sent, failed",
+                    "limit": "integer",
+                    "q": "string",
+                }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn bad_enum_values_are_rejected_in_strict_mode() {
+        let response = get(QueryMode::Strict, "?status=new").await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json(&response);
+        assert!(body["error"].as_str().unwrap().starts_with("invalid query parameter: unknown variant `new`"), "{}", body);
+
+        let response = get(QueryMode::Strict, "?limit=ten").await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn lenient_mode_drops_bad_parameters_with_a_warning() {
+        let response = get(QueryMode::Lenient, "?status=new&limit=5&stauts=sent").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.body(), "None Some(5)");
+
+        let warning = response.headers()[WARNING].to_str().unwrap();
+        assert!(warning.starts_with("299 - \"unknown query parameter: stauts; invalid value for status: "), "{}", warning);
+        assert!(warning.ends_with('"'), "{}", warning);
+    }
+
+    #[tokio::test]
+    async fn lenient_warning_escapes_parameter_names() {
+        let response = get(QueryMode::Lenient, "?%22caf%C3%A9%0A=1").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[WARNING],
+            "299 - \"unknown query parameter: \\\"caf\\u{e9}\\n\""
+        );
+    }
+
+    #[test]
+    fn mode_defaults_to_lenient() {
+        assert_eq!(QueryMode::parse(""), QueryMode::Lenient);
+        assert_eq!(QueryMode::parse(" Strict "), QueryMode::Strict);
+        assert_eq!(QueryMode::parse("strictest"), QueryMode::Lenient);
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use std::env;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use warp::{Filter, Reply};
+
+use crate::geoip;
+use personal_api::models::ContactError;
+
+const DEFAULT_LIMIT: u32 = 5;
+const DEFAULT_WINDOW_SECS: u64 = 600;
+
+// Rejection for a client that has used up its submissions for now
+#[derive(Debug)]
+struct RateLimited {
+    retry_after_secs: u64,
+}
+
+impl warp::reject::Reject for RateLimited {}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+struct Buckets {
+    by_ip: HashMap<IpAddr, Bucket>,
+    last_sweep: Instant,
+}
+
+// Per-IP token bucket: each IP can send CONTACT_RATE_LIMIT submissions at
+// once, refilled evenly over CONTACT_RATE_WINDOW_SECS
+pub struct RateLimiter {
+    capacity: f64,
+    window: Duration,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    // None when CONTACT_RATE_LIMIT is 0
+    pub fn from_env() -> Option<Self> {
+        let limit = match env::var("CONTACT_RATE_LIMIT") {
+            Ok(value) => value.trim().parse::<u32>().unwrap_or_else(|_| {
+                tracing::warn!("Invalid CONTACT_RATE_LIMIT '{}', using {}", value, DEFAULT_LIMIT);
+                DEFAULT_LIMIT
+            }),
+            Err(_) => DEFAULT_LIMIT,
+        };
+        let window_secs = match env::var("CONTACT_RATE_WINDOW_SECS") {
+            Ok(value) => value.trim().parse::<u64>().ok().filter(|secs| *secs > 0).unwrap_or_else(|| {
+                tracing::warn!("Invalid CONTACT_RATE_WINDOW_SECS '{}', using {}", value, DEFAULT_WINDOW_SECS);
+                DEFAULT_WINDOW_SECS
+            }),
+            Err(_) => DEFAULT_WINDOW_SECS,
+        };
+
+        Self::new(limit, Duration::from_secs(window_secs))
+    }
+
+    // `limit` submissions per IP at once, refilled evenly over `window`.
+    // None when `limit` is 0.
+    pub fn new(limit: u32, window: Duration) -> Option<Self> {
+        if limit == 0 {
+            tracing::info!("Contact rate limiting disabled");
+            return None;
+        }
+        Some(RateLimiter {
+            capacity: f64::from(limit),
+            window,
+            buckets: Mutex::new(Buckets {
+                by_ip: HashMap::new(),
+                last_sweep: Instant::now(),
+            }),
+        })
+    }
+
+    fn refill_per_sec(&self) -> f64 {
+        self.capacity / self.window.as_secs_f64()
+    }
+
+    // Take a token for this IP, or say how many seconds until one is free
+    fn take(&self, ip: IpAddr) -> Result<(), u64> {
+        let now = Instant::now();
+        let rate = self.refill_per_sec();
+        let mut buckets = self.buckets.lock().unwrap();
+
+        // A bucket idle for a whole window is full again, the same as no bucket
+        if now.duration_since(buckets.last_sweep) >= self.window {
+            let window = self.window;
+            buckets.by_ip.retain(|_, bucket| now.duration_since(bucket.updated) < window);
+            buckets.last_sweep = now;
+        }
+
+        let bucket = buckets.by_ip.entry(ip).or_insert(Bucket {
+            tokens: self.capacity,
+            updated: now,
+        });
+        bucket.tokens = (bucket.tokens + now.duration_since(bucket.updated).as_secs_f64() * rate).min(self.capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(((1.0 - bucket.tokens) / rate).ceil() as u64)
+        }
+    }
+}
+
+// Reject clients over the limit, keyed by the same client IP the GeoIP lookup uses
+pub fn limit(
+    limiter: Option<Arc<RateLimiter>>,
+    trusted_proxies: Arc<Vec<IpAddr>>,
+) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::addr::remote()
+        .and(warp::header::optional::<String>("x-forwarded-for"))
+        .and_then(move |remote: Option<SocketAddr>, forwarded_for: Option<String>| {
+            let limiter = limiter.clone();
+            let ip = geoip::client_ip(remote, forwarded_for.as_deref(), &trusted_proxies);
+            async move {
+                match (limiter, ip) {
+                    (Some(limiter), Some(ip)) => limiter.take(ip).map_err(|retry_after_secs| {
+                        tracing::warn!("Rate limited contact form from {}, retry in {}s", ip, retry_after_secs);
+                        warp::reject::custom(RateLimited { retry_after_secs })
+                    }),
+                    _ => Ok(()),
+                }
+            }
+        })
+        .untuple_one()
+}
+
+pub async fn handle_rejection(err: warp::Rejection) -> Result<warp::reply::Response, warp::Rejection> {
+    if let Some(RateLimited { retry_after_secs }) = err.find::<RateLimited>() {
+        let reply = warp::reply::with_status(
+            warp::reply::json(
+                &ContactError::new("Too many submissions, please try again later")
+                    .with_code("RATE_LIMITED")
+                    .with_retry_after(*retry_after_secs),
+            ),
+            warp::http::StatusCode::TOO_MANY_REQUESTS,
+        );
+        return Ok(warp::reply::with_header(reply, "Retry-After", retry_after_secs.to_string()).into_response());
+    }
+
+    Err(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn each_ip_gets_its_own_bucket() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(600)).unwrap();
+        assert_eq!(limiter.take(ip("198.51.100.1")), Ok(()));
+        assert_eq!(limiter.take(ip("198.51.100.1")), Ok(()));
+        // One token comes back every 300s
+        assert_eq!(limiter.take(ip("198.51.100.1")), Err(300));
+        assert_eq!(limiter.take(ip("198.51.100.2")), Ok(()));
+    }
+
+    #[test]
+    fn tokens_refill_over_the_window() {
+        let limiter = RateLimiter::new(1, Duration::from_millis(200)).unwrap();
+        assert_eq!(limiter.take(ip("198.51.100.1")), Ok(()));
+        assert!(limiter.take(ip("198.51.100.1")).is_err());
+
+        std::thread::sleep(Duration::from_millis(250));
+        assert_eq!(limiter.take(ip("198.51.100.1")), Ok(()));
+    }
+
+    #[test]
+    fn a_zero_limit_disables_it() {
+        assert!(RateLimiter::new(0, Duration::from_secs(600)).is_none());
+    }
+}
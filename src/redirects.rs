@@ -0,0 +1,271 @@
+use std::env;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use warp::http::{Method, StatusCode};
+use warp::Filter;
+
+// A configured redirect. A source ending in '*' matches by prefix and the
+// remainder replaces '*' in the target.
+struct RedirectRule {
+    source: String,
+    target: String,
+    status: StatusCode,
+    hits: AtomicU64,
+}
+
+impl RedirectRule {
+    fn target_for(&self, path: &str) -> Option<String> {
+        let target = match self.source.strip_suffix('*') {
+            Some(prefix) => {
+                let rest = path.strip_prefix(prefix)?;
+                self.target.replacen('*', rest, 1)
+            }
+            None if path == self.source => self.target.clone(),
+            None => return None,
+        };
+
+        // Captured path segments must never turn a local target into another host
+        if is_absolute(&self.target) || is_local_path(&target) {
+            Some(target)
+        } else {
+            None
+        }
+    }
+}
+
+// Redirect table from REDIRECT_RULES, evaluated before routing
+pub struct RedirectTable {
+    rules: Vec<RedirectRule>,
+}
+
+impl RedirectTable {
+    // Format: REDIRECT_RULES="/contact.php=>/api/contact:308;/old/*=>/api/*:301"
+    // Targets must be local paths unless written as absolute http(s) URLs.
+    pub fn from_env() -> Self {
+        Self::parse(&env::var("REDIRECT_RULES").unwrap_or_default())
+    }
+
+    pub fn parse(config: &str) -> Self {
+        let rules = config
+            .split(';')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| {
+                let rule = parse_rule(entry);
+                if rule.is_none() {
+                    tracing::warn!("Ignoring invalid REDIRECT_RULES entry: {}", entry);
+                }
+                rule
+            })
+            .collect();
+
+        RedirectTable { rules }
+    }
+
+    fn resolve(&self, path: &str) -> Option<(String, StatusCode)> {
+        self.rules.iter().find_map(|rule| {
+            let target = rule.target_for(path)?;
+            let hits = rule.hits.fetch_add(1, Ordering::Relaxed) + 1;
+            tracing::info!("Redirect {} -> {} ({} hits for rule {})", path, target, hits, rule.source);
+            Some((target, rule.status))
+        })
+    }
+
+    // Hit counts per rule, for spotting when old clients have gone away
+    pub fn hits(&self) -> serde_json::Value {
+        self.rules
+            .iter()
+            .map(|rule| {
+                serde_json::json!({
+                    "source": rule.source,
+                    "target": rule.target,
+                    "status": rule.status.as_u16(),
+                    "hits": rule.hits.load(Ordering::Relaxed),
+                })
+            })
+            .collect()
+    }
+}
+
+fn parse_rule(entry: &str) -> Option<RedirectRule> {
+    let (source, rest) = entry.split_once("=>")?;
+    let (target, status) = match rest.rsplit_once(':') {
+        Some((target, status)) if status.trim().chars().all(|c| c.is_ascii_digit()) => {
+            (target.trim(), status.trim().parse::<u16>().ok()?)
+        }
+        _ => (rest.trim(), 301),
+    };
+    let source = source.trim();
+
+    let status = match status {
+        301 => StatusCode::MOVED_PERMANENTLY,
+        307 => StatusCode::TEMPORARY_REDIRECT,
+        308 => StatusCode::PERMANENT_REDIRECT,
+        _ => return None,
+    };
+
+    if !is_local_path(source) || !(is_local_path(target) || is_absolute(target)) {
+        return None;
+    }
+
+    // In an absolute target the '*' has to sit in the path, after the host
+    if is_absolute(target) {
+        if let Some(star) = target.find('*') {
+            let path_start = target.find("://").and_then(|i| target[i + 3..].find('/').map(|j| i + 3 + j))?;
+            if star < path_start {
+                return None;
+            }
+        }
+    }
+
+    Some(RedirectRule {
+        source: source.to_string(),
+        target: target.to_string(),
+        status,
+        hits: AtomicU64::new(0),
+    })
+}
+
+fn is_absolute(target: &str) -> bool {
+    target.starts_with("https://") || target.starts_with("http://")
+}
+
+// A path on this host: "/x" but not "//host" or anything with backslashes
+fn is_local_path(target: &str) -> bool {
+    target.starts_with('/') && !target.starts_with("//") && !target.contains('\\')
+}
+
+// Answers configured redirects and trailing-slash normalization; every other
+// request is rejected as not found so it falls through to the real routes.
+// Rules are written without BASE_PATH, which is added back to local targets.
+pub fn filter(
+    table: Arc<RedirectTable>,
+    base_path: String,
+) -> impl Filter<Extract = (warp::reply::Response,), Error = warp::Rejection> + Clone {
+    warp::path::full()
+        .and(warp::method())
+        .and(
+            warp::query::raw()
+                .or(warp::any().map(String::new))
+                .unify(),
+        )
+        .and_then(move |path: warp::path::FullPath, method: Method, query: String| {
+            let table = table.clone();
+            let base_path = base_path.clone();
+            async move {
+                let path = match path.as_str().strip_prefix(base_path.as_str()) {
+                    Some("") => "/",
+                    Some(path) if path.starts_with('/') => path,
+                    _ => return Err(warp::reject::not_found()),
+                };
+
+                let (target, status) = match table.resolve(path) {
+                    Some(redirect) => redirect,
+                    // Canonical paths have no trailing slash; 308 keeps POST bodies.
+                    // A backslash would let "/\host/" become a Location that
+                    // browsers read as another host, so such paths are left to 404.
+                    None if path.len() > 1 && path.ends_with('/') && !path.contains('\\') => {
+                        let status = if method == Method::GET || method == Method::HEAD {
+                            StatusCode::MOVED_PERMANENTLY
+                        } else {
+                            StatusCode::PERMANENT_REDIRECT
+                        };
+                        (format!("/{}", path.trim_matches('/')), status)
+                    }
+                    None => return Err(warp::reject::not_found()),
+                };
+
+                let target = if is_absolute(&target) {
+                    target
+                } else {
+                    format!("{}{}", base_path, target)
+                };
+                let location = if query.is_empty() || target.contains('?') {
+                    target
+                } else {
+                    format!("{}?{}", target, query)
+                };
+
+                warp::http::Response::builder()
+                    .status(status)
+                    .header("Location", location)
+                    .body(warp::hyper::Body::empty())
+                    .map_err(|_| warp::reject::not_found())
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (status, Location) for a request, or None when it falls through
+    async fn redirect(rules: &str, base_path: &str, method: &str, path: &str) -> Option<(u16, String)> {
+        let filter = filter(Arc::new(RedirectTable::parse(rules)), base_path.to_string());
+        let response = warp::test::request().method(method).path(path).filter(&filter).await.ok()?;
+        let location = response.headers()["location"].to_str().unwrap().to_string();
+        Some((response.status().as_u16(), location))
+    }
+
+    #[tokio::test]
+    async fn rules_redirect_and_keep_the_query() {
+        let rules = "/contact.php=>/api/contact:308;/old/*=>/api/*";
+        assert_eq!(redirect(rules, "", "POST", "/contact.php").await, Some((308, "/api/contact".to_string())));
+        assert_eq!(redirect(rules, "", "GET", "/old/resume?x=1").await, Some((301, "/api/resume?x=1".to_string())));
+        assert_eq!(redirect(rules, "/svc", "GET", "/svc/old/stats").await, Some((301, "/svc/api/stats".to_string())));
+        assert_eq!(redirect(rules, "", "GET", "/api/resume").await, None);
+    }
+
+    #[tokio::test]
+    async fn captured_segments_never_leave_the_host() {
+        let rules = "/go/*=>/*:307";
+        assert_eq!(redirect(rules, "", "GET", "/go/api").await, Some((307, "/api".to_string())));
+        assert_eq!(redirect(rules, "", "GET", "/go//evil.example").await, None);
+        assert_eq!(redirect(rules, "", "GET", "/go/%5Cevil.example").await, Some((307, "/%5Cevil.example".to_string())));
+
+        let table = RedirectTable::parse(rules);
+        assert_eq!(table.resolve("/go/\\evil.example"), None);
+        assert_eq!(table.resolve("/go//evil.example"), None);
+    }
+
+    #[test]
+    fn rules_pointing_at_other_hosts_are_rejected() {
+        for rule in [
+            "/a=>//evil.example",
+            "/a=>/\\evil.example",
+            "//a=>/b",
+            "/a=>evil.example/b",
+            "/a=>https://*.example/b",
+            "/a=>/b:302",
+        ] {
+            assert!(parse_rule(rule).is_none(), "{} should be rejected", rule);
+        }
+    }
+
+    #[tokio::test]
+    async fn absolute_targets_are_allowed() {
+        let rules = "/blog=>https://blog.example.com/:308;/posts/*=>https://blog.example.com/p/*";
+        assert_eq!(
+            redirect(rules, "/svc", "GET", "/svc/blog?ref=x").await,
+            Some((308, "https://blog.example.com/?ref=x".to_string()))
+        );
+        assert_eq!(
+            redirect(rules, "", "GET", "/posts/hello").await,
+            Some((301, "https://blog.example.com/p/hello".to_string()))
+        );
+        // A wildcard can't reach into the host of an absolute target
+        assert_eq!(
+            redirect(rules, "", "GET", "/posts/@evil.example").await,
+            Some((301, "https://blog.example.com/p/@evil.example".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn trailing_slashes_are_trimmed_on_this_host() {
+        assert_eq!(redirect("", "", "GET", "/api/resume/").await, Some((301, "/api/resume".to_string())));
+        assert_eq!(redirect("", "", "POST", "/api/contact/?a=1").await, Some((308, "/api/contact?a=1".to_string())));
+        assert_eq!(redirect("", "", "GET", "//evil.example/").await, Some((301, "/evil.example".to_string())));
+        assert_eq!(redirect("", "", "GET", "/\\evil.example/").await, None);
+        assert_eq!(redirect("", "", "GET", "/").await, None);
+    }
+}
@@ -0,0 +1,633 @@
+use chrono::{DateTime, SecondsFormat, SubsecRound, Utc};
+use personal_api::models::ErrorMessage;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use warp::http::header::{self, HeaderMap, HeaderValue};
+use warp::http::{Method, StatusCode};
+use warp::hyper::body::Bytes;
+use warp::hyper::Body;
+use warp::Reply;
+
+const DEFAULT_RESUME_PATH: &str = "assets/Michael Henry Resume - Staff Software Engineer.pdf";
+
+// The resume PDF served by every /api/resume route, from RESUME_PATH
+pub fn path_from_env() -> String {
+    env::var("RESUME_PATH")
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| DEFAULT_RESUME_PATH.to_string())
+}
+
+// Metadata for GET /api/resume/meta. PDF fields are None when the file
+// could not be parsed; the file fields are always present.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResumeMeta {
+    #[serde(rename = "sizeBytes")]
+    size_bytes: u64,
+    sha256: String,
+    #[serde(rename = "lastModified")]
+    last_modified: String,
+    #[serde(rename = "pageCount")]
+    page_count: Option<usize>,
+    title: Option<String>,
+    author: Option<String>,
+}
+
+// The file as served, with its validators
+struct CachedFile {
+    path: String,
+    modified: SystemTime,
+    bytes: Bytes,
+    sha256: String,
+    etag: String,
+    last_modified: DateTime<Utc>,
+}
+
+// File contents, re-read only when the path, size or mtime changes
+static FILE_CACHE: Mutex<Option<Arc<CachedFile>>> = Mutex::new(None);
+
+fn read_file(path: &str) -> Result<Arc<CachedFile>, std::io::Error> {
+    let metadata = fs::metadata(path)?;
+    let modified = metadata.modified()?;
+
+    let mut cache = FILE_CACHE.lock().unwrap();
+    if let Some(file) = cache.as_ref() {
+        if file.path == path && file.modified == modified && file.bytes.len() as u64 == metadata.len() {
+            return Ok(file.clone());
+        }
+    }
+
+    let bytes = Bytes::from(fs::read(path)?);
+    let sha256 = format!("{:x}", Sha256::digest(&bytes));
+    let file = Arc::new(CachedFile {
+        path: path.to_string(),
+        modified,
+        etag: format!("\"{}\"", sha256),
+        sha256,
+        bytes,
+        // HTTP dates have whole seconds, so compare at that precision
+        last_modified: DateTime::<Utc>::from(modified).trunc_subsecs(0),
+    });
+    *cache = Some(file.clone());
+    Ok(file)
+}
+
+struct Parsed {
+    path: String,
+    modified: SystemTime,
+    size_bytes: u64,
+    meta: Arc<ResumeMeta>,
+    text: Option<Arc<String>>,
+}
+
+// Parsed resume, recomputed only when the path, size or mtime changes
+static CACHE: Mutex<Option<Parsed>> = Mutex::new(None);
+
+fn load(path: &str) -> Result<(Arc<ResumeMeta>, Option<Arc<String>>), anyhow::Error> {
+    let file = read_file(path)?;
+    let modified = file.modified;
+    let size_bytes = file.bytes.len() as u64;
+
+    let mut cache = CACHE.lock().unwrap();
+    if let Some(parsed) = cache.as_ref() {
+        if parsed.path == path && parsed.modified == modified && parsed.size_bytes == size_bytes {
+            return Ok((parsed.meta.clone(), parsed.text.clone()));
+        }
+    }
+
+    let data = &file.bytes;
+    let (page_count, title, author, text) = match lopdf::Document::load_mem(data) {
+        Ok(document) => {
+            let pages: Vec<u32> = document.get_pages().keys().copied().collect();
+            let text = document
+                .extract_text(&pages)
+                .map_err(|e| tracing::warn!("Could not extract text from {}: {}", path, e))
+                .ok()
+                .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+                .filter(|text| !text.is_empty());
+            (
+                Some(pages.len()),
+                info_field(&document, b"Title"),
+                info_field(&document, b"Author"),
+                text,
+            )
+        }
+        Err(e) => {
+            tracing::warn!("Could not parse {} as PDF, serving file metadata only: {}", path, e);
+            (None, None, None, None)
+        }
+    };
+
+    let meta = Arc::new(ResumeMeta {
+        size_bytes,
+        sha256: file.sha256.clone(),
+        last_modified: DateTime::<Utc>::from(modified).to_rfc3339_opts(SecondsFormat::Secs, true),
+        page_count,
+        title,
+        author,
+    });
+    let text = text.map(Arc::new);
+
+    *cache = Some(Parsed {
+        path: path.to_string(),
+        modified,
+        size_bytes,
+        meta: meta.clone(),
+        text: text.clone(),
+    });
+    Ok((meta, text))
+}
+
+// Reading and parsing the file block, so handlers run them on the blocking
+// pool rather than on an async worker
+async fn read_file_blocking(path: Arc<String>) -> Result<Arc<CachedFile>, std::io::Error> {
+    tokio::task::spawn_blocking(move || read_file(&path))
+        .await
+        .map_err(std::io::Error::other)?
+}
+
+async fn load_blocking(path: Arc<String>) -> Result<(Arc<ResumeMeta>, Option<Arc<String>>), anyhow::Error> {
+    tokio::task::spawn_blocking(move || load(&path)).await?
+}
+
+// A text string from the document info dictionary, if present
+fn info_field(document: &lopdf::Document, key: &[u8]) -> Option<String> {
+    let info = document.trailer.get_deref(b"Info", document).ok()?.as_dict().ok()?;
+    let value = lopdf::decode_text_string(info.get_deref(key, document).ok()?).ok()?;
+    let value = crate::sanitize_input(&value);
+    (!value.is_empty()).then_some(value)
+}
+
+fn not_available(message: &str) -> warp::reply::Response {
+    warp::reply::with_status(
+        warp::reply::json(&ErrorMessage::new(message)),
+        warp::http::StatusCode::NOT_FOUND,
+    )
+    .into_response()
+}
+
+// GET /api/resume/meta - File and PDF metadata without the download
+#[tracing::instrument(skip_all, fields(route = "/api/resume/meta"))]
+pub async fn handle_meta(path: Arc<String>) -> Result<warp::reply::Response, warp::Rejection> {
+    match load_blocking(path).await {
+        Ok((meta, _)) => Ok(warp::reply::json(meta.as_ref()).into_response()),
+        Err(e) => {
+            tracing::warn!("Resume metadata unavailable: {}", e);
+            Ok(not_available("Resume not found"))
+        }
+    }
+}
+
+// GET /api/resume/text - Plain text of the resume for search snippets
+#[tracing::instrument(skip_all, fields(route = "/api/resume/text"))]
+pub async fn handle_text(path: Arc<String>) -> Result<warp::reply::Response, warp::Rejection> {
+    match load_blocking(path).await {
+        Ok((_, Some(text))) => Ok(warp::reply::with_header(
+            text.as_ref().clone(),
+            "Content-Type",
+            "text/plain; charset=utf-8",
+        )
+        .into_response()),
+        Ok((_, None)) => Ok(not_available("Resume text is not available")),
+        Err(e) => {
+            tracing::warn!("Resume text unavailable: {}", e);
+            Ok(not_available("Resume not found"))
+        }
+    }
+}
+
+// What a Range header asks for, given the file length
+#[derive(Debug, PartialEq)]
+enum ByteRange {
+    Full,
+    // Inclusive start and end
+    Partial(u64, u64),
+    Unsatisfiable,
+}
+
+// Single byte ranges only; multiple ranges and malformed headers get the whole
+// file, which a client has to accept
+fn parse_range(value: &str, len: u64) -> ByteRange {
+    let Some(spec) = value.trim().strip_prefix("bytes=") else {
+        return ByteRange::Full;
+    };
+    if spec.contains(',') {
+        return ByteRange::Full;
+    }
+    let Some((start, end)) = spec.split_once('-') else {
+        return ByteRange::Full;
+    };
+
+    let (start, end) = (start.trim(), end.trim());
+    if start.is_empty() {
+        // Suffix range: the last N bytes
+        return match end.parse::<u64>() {
+            Ok(0) => ByteRange::Unsatisfiable,
+            Ok(_) if len == 0 => ByteRange::Unsatisfiable,
+            Ok(suffix) => ByteRange::Partial(len.saturating_sub(suffix), len - 1),
+            Err(_) => ByteRange::Full,
+        };
+    }
+
+    let Ok(start) = start.parse::<u64>() else {
+        return ByteRange::Full;
+    };
+    let end = match end {
+        "" => len.saturating_sub(1),
+        end => match end.parse::<u64>() {
+            Ok(end) if end >= start => end.min(len.saturating_sub(1)),
+            _ => return ByteRange::Full,
+        },
+    };
+    if start >= len {
+        ByteRange::Unsatisfiable
+    } else {
+        ByteRange::Partial(start, end)
+    }
+}
+
+// If-None-Match: a list of entity tags or "*", compared weakly
+fn etag_matches(value: &str, etag: &str) -> bool {
+    value
+        .split(',')
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == etag)
+}
+
+fn http_date(time: DateTime<Utc>) -> String {
+    time.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+fn parse_http_date(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(value.trim()).ok().map(|time| time.with_timezone(&Utc))
+}
+
+// The client already has this version: If-None-Match wins over If-Modified-Since
+fn not_modified(headers: &HeaderMap, file: &CachedFile) -> bool {
+    match headers.get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok()) {
+        Some(value) => etag_matches(value, &file.etag),
+        None => headers
+            .get(header::IF_MODIFIED_SINCE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_http_date)
+            .is_some_and(|since| file.last_modified <= since),
+    }
+}
+
+// A Range only applies when If-Range, if sent, still names this version
+fn range_applies(headers: &HeaderMap, file: &CachedFile) -> bool {
+    match headers.get(header::IF_RANGE).and_then(|v| v.to_str().ok()) {
+        Some(value) if value.trim().starts_with('"') => value.trim() == file.etag,
+        Some(value) => parse_http_date(value).is_some_and(|date| date == file.last_modified),
+        None => true,
+    }
+}
+
+// inline, with the file's own name; non-ASCII names also get an RFC 5987 form
+fn content_disposition(path: &str) -> String {
+    let name = Path::new(path).file_name().and_then(|name| name.to_str()).unwrap_or("resume.pdf");
+    let fallback: String = name
+        .chars()
+        .map(|c| if (c.is_ascii_graphic() || c == ' ') && c != '"' && c != '\\' { c } else { '_' })
+        .collect();
+    if fallback == name {
+        return format!("inline; filename=\"{}\"", name);
+    }
+
+    let encoded: String = name
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+    format!("inline; filename=\"{}\"; filename*=UTF-8''{}", fallback, encoded)
+}
+
+// GET and HEAD /api/resume - The PDF, with validators and byte ranges
+#[tracing::instrument(skip_all, fields(route = "/api/resume"))]
+pub async fn handle_download(
+    method: Method,
+    headers: HeaderMap,
+    path: Arc<String>,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    let file = match read_file_blocking(path.clone()).await {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(not_available("Resume not found")),
+        Err(e) => {
+            tracing::error!("Failed to read resume {}: {}", path, e);
+            return Ok(warp::reply::with_status(
+                warp::reply::json(&ErrorMessage::new("Failed to read resume")),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+            .into_response());
+        }
+    };
+
+    let mut response = warp::reply::Response::new(Body::empty());
+    let response_headers = response.headers_mut();
+    response_headers.insert(header::ETAG, HeaderValue::from_str(&file.etag).unwrap());
+    response_headers.insert(header::LAST_MODIFIED, HeaderValue::from_str(&http_date(file.last_modified)).unwrap());
+    response_headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+
+    if not_modified(&headers, &file) {
+        *response.status_mut() = StatusCode::NOT_MODIFIED;
+        return Ok(response);
+    }
+
+    let len = file.bytes.len() as u64;
+    let range = match headers.get(header::RANGE).and_then(|v| v.to_str().ok()) {
+        Some(value) if range_applies(&headers, &file) => parse_range(value, len),
+        _ => ByteRange::Full,
+    };
+
+    // A download is a full GET or the first chunk of a ranged one
+    if method == Method::GET && matches!(range, ByteRange::Full | ByteRange::Partial(0, _)) {
+        crate::stats::record_resume_download();
+    }
+
+    let (status, body) = match range {
+        ByteRange::Full => (StatusCode::OK, file.bytes.clone()),
+        ByteRange::Partial(start, end) => {
+            response.headers_mut().insert(
+                header::CONTENT_RANGE,
+                HeaderValue::from_str(&format!("bytes {}-{}/{}", start, end, len)).unwrap(),
+            );
+            (StatusCode::PARTIAL_CONTENT, file.bytes.slice(start as usize..end as usize + 1))
+        }
+        ByteRange::Unsatisfiable => {
+            let mut response = warp::reply::with_status(
+                warp::reply::json(&ErrorMessage::new("Requested range not satisfiable")),
+                StatusCode::RANGE_NOT_SATISFIABLE,
+            )
+            .into_response();
+            response.headers_mut().insert(
+                header::CONTENT_RANGE,
+                HeaderValue::from_str(&format!("bytes */{}", len)).unwrap(),
+            );
+            return Ok(response);
+        }
+    };
+
+    *response.status_mut() = status;
+    let response_headers = response.headers_mut();
+    response_headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/pdf"));
+    response_headers.insert(header::CONTENT_LENGTH, HeaderValue::from(body.len()));
+    if let Ok(value) = HeaderValue::from_str(&content_disposition(&path)) {
+        response_headers.insert(header::CONTENT_DISPOSITION, value);
+    }
+    if method != Method::HEAD {
+        *response.body_mut() = Body::from(body);
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use warp::Filter;
+
+    const FIXTURE: &[u8] = include_bytes!("../testdata/resume.pdf");
+
+    // The /api/resume/meta and /text routes, as main builds them
+    fn details(path: String) -> impl Filter<Extract = (warp::reply::Response,), Error = warp::Rejection> + Clone {
+        let path = Arc::new(path);
+        let with_path = warp::any().map(move || path.clone());
+        warp::path!("api" / "resume" / "meta")
+            .and(with_path.clone())
+            .and_then(handle_meta)
+            .or(warp::path!("api" / "resume" / "text").and(with_path).and_then(handle_text))
+            .unify()
+    }
+
+    async fn get(path: &str, route: &str) -> (StatusCode, Bytes) {
+        let response = warp::test::request().path(route).reply(&details(path.to_string())).await;
+        (response.status(), response.body().clone())
+    }
+
+    fn json(body: &Bytes) -> serde_json::Value {
+        serde_json::from_slice(body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn meta_describes_the_fixture_pdf() {
+        let dir = TempDir::new();
+        let path = dir.write("resume.pdf", FIXTURE);
+
+        let (status, body) = get(&path, "/api/resume/meta").await;
+        assert_eq!(status, StatusCode::OK);
+        let meta = json(&body);
+        assert_eq!(meta["sizeBytes"], FIXTURE.len());
+        assert_eq!(meta["sha256"], format!("{:x}", Sha256::digest(FIXTURE)));
+        assert_eq!(meta["pageCount"], 1);
+        assert_eq!(meta["title"], "Jane Doe Resume");
+        assert_eq!(meta["author"], "Jane Doe");
+        assert!(meta["lastModified"].as_str().unwrap().ends_with('Z'));
+    }
+
+    #[tokio::test]
+    async fn text_is_the_pdf_text_with_whitespace_collapsed() {
+        let dir = TempDir::new();
+        let path = dir.write("resume.pdf", FIXTURE);
+
+        let response = warp::test::request().path("/api/resume/text").reply(&details(path)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "text/plain; charset=utf-8");
+        assert_eq!(response.body().as_ref(), b"Jane Doe Staff Software Engineer");
+    }
+
+    #[tokio::test]
+    async fn corrupted_pdf_falls_back_to_file_metadata() {
+        let dir = TempDir::new();
+        let path = dir.write("resume.pdf", &FIXTURE[..FIXTURE.len() / 2]);
+
+        let (status, body) = get(&path, "/api/resume/meta").await;
+        assert_eq!(status, StatusCode::OK);
+        let meta = json(&body);
+        assert_eq!(meta["sizeBytes"], FIXTURE.len() / 2);
+        assert_eq!(meta["pageCount"], serde_json::Value::Null);
+        assert_eq!(meta["title"], serde_json::Value::Null);
+
+        let (status, body) = get(&path, "/api/resume/text").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(json(&body)["error"], "Resume text is not available");
+    }
+
+    #[tokio::test]
+    async fn missing_file_is_not_found() {
+        let dir = TempDir::new();
+        let (status, body) = get(&dir.path("missing.pdf"), "/api/resume/meta").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(json(&body)["error"], "Resume not found");
+    }
+
+    #[tokio::test]
+    async fn files_at_different_paths_are_cached_separately() {
+        let dir = TempDir::new();
+        let good = dir.write("good.pdf", FIXTURE);
+        let broken = dir.write("broken.pdf", &FIXTURE[..FIXTURE.len() / 2]);
+
+        assert_eq!(json(&get(&good, "/api/resume/meta").await.1)["pageCount"], 1);
+        assert_eq!(json(&get(&broken, "/api/resume/meta").await.1)["pageCount"], serde_json::Value::Null);
+        assert_eq!(json(&get(&good, "/api/resume/meta").await.1)["pageCount"], 1);
+    }
+
+    // GET and HEAD /api/resume, as main builds it
+    fn download(path: String) -> impl Filter<Extract = (warp::reply::Response,), Error = warp::Rejection> + Clone {
+        let path = Arc::new(path);
+        warp::get()
+            .or(warp::head())
+            .unify()
+            .and(warp::method())
+            .and(warp::header::headers_cloned())
+            .and(warp::any().map(move || path.clone()))
+            .and_then(handle_download)
+    }
+
+    async fn fetch(path: &str, method: &str, headers: &[(&str, &str)]) -> warp::http::Response<Bytes> {
+        let mut request = warp::test::request().method(method).path("/api/resume");
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        request.reply(&download(path.to_string())).await
+    }
+
+    #[test]
+    fn ranges() {
+        assert_eq!(parse_range("bytes=0-9", 100), ByteRange::Partial(0, 9));
+        assert_eq!(parse_range("bytes=90-", 100), ByteRange::Partial(90, 99));
+        assert_eq!(parse_range("bytes=90-500", 100), ByteRange::Partial(90, 99));
+        assert_eq!(parse_range("bytes=-10", 100), ByteRange::Partial(90, 99));
+        assert_eq!(parse_range("bytes=-500", 100), ByteRange::Partial(0, 99));
+        assert_eq!(parse_range("bytes=100-", 100), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range("bytes=-0", 100), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range("bytes=-5", 0), ByteRange::Unsatisfiable);
+        // Anything else gets the whole file
+        assert_eq!(parse_range("bytes=0-1,5-9", 100), ByteRange::Full);
+        assert_eq!(parse_range("bytes=9-0", 100), ByteRange::Full);
+        assert_eq!(parse_range("items=0-9", 100), ByteRange::Full);
+        assert_eq!(parse_range("bytes=a-b", 100), ByteRange::Full);
+    }
+
+    #[test]
+    fn file_names_in_content_disposition() {
+        assert_eq!(content_disposition("/app/assets/resume.pdf"), "inline; filename=\"resume.pdf\"");
+        assert_eq!(
+            content_disposition("/app/assets/Résumé \"2026\".pdf"),
+            "inline; filename=\"R_sum_ _2026_.pdf\"; filename*=UTF-8''R%C3%A9sum%C3%A9%20%222026%22.pdf"
+        );
+    }
+
+    #[tokio::test]
+    async fn get_serves_the_pdf_with_validators() {
+        let dir = TempDir::new();
+        let path = dir.write("resume.pdf", FIXTURE);
+
+        let response = fetch(&path, "GET", &[]).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.body().as_ref(), FIXTURE);
+        let headers = response.headers();
+        assert_eq!(headers["content-type"], "application/pdf");
+        assert_eq!(headers["content-length"], FIXTURE.len().to_string().as_str());
+        assert_eq!(headers["accept-ranges"], "bytes");
+        assert_eq!(headers["content-disposition"], "inline; filename=\"resume.pdf\"");
+        assert!(headers["etag"].to_str().unwrap().starts_with('"'));
+        assert!(headers["last-modified"].to_str().unwrap().ends_with(" GMT"));
+    }
+
+    #[tokio::test]
+    async fn head_has_the_headers_but_no_body() {
+        let dir = TempDir::new();
+        let path = dir.write("resume.pdf", FIXTURE);
+
+        let response = fetch(&path, "HEAD", &[]).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-length"], FIXTURE.len().to_string().as_str());
+        assert!(response.body().is_empty());
+    }
+
+    #[tokio::test]
+    async fn matching_validators_get_304() {
+        let dir = TempDir::new();
+        let path = dir.write("resume.pdf", FIXTURE);
+        let first = fetch(&path, "GET", &[]).await;
+        let etag = first.headers()["etag"].to_str().unwrap().to_string();
+        let last_modified = first.headers()["last-modified"].to_str().unwrap().to_string();
+
+        for headers in [
+            vec![("if-none-match", etag.clone())],
+            vec![("if-none-match", format!("\"other\", W/{}", etag))],
+            vec![("if-none-match", "*".to_string())],
+            vec![("if-modified-since", last_modified.clone())],
+        ] {
+            let headers: Vec<_> = headers.iter().map(|(name, value)| (*name, value.as_str())).collect();
+            let response = fetch(&path, "GET", &headers).await;
+            assert_eq!(response.status(), StatusCode::NOT_MODIFIED, "{:?}", headers);
+            assert!(response.body().is_empty());
+            assert_eq!(response.headers()["etag"], etag.as_str());
+        }
+
+        // If-None-Match wins over a matching If-Modified-Since
+        let stale = fetch(&path, "GET", &[("if-none-match", "\"other\""), ("if-modified-since", &last_modified)]).await;
+        assert_eq!(stale.status(), StatusCode::OK);
+        let older = fetch(&path, "GET", &[("if-modified-since", "Mon, 01 Jan 2001 00:00:00 GMT")]).await;
+        assert_eq!(older.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn ranges_get_206() {
+        let dir = TempDir::new();
+        let path = dir.write("resume.pdf", FIXTURE);
+        let len = FIXTURE.len();
+
+        let response = fetch(&path, "GET", &[("range", "bytes=0-9")]).await;
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.headers()["content-range"], format!("bytes 0-9/{}", len).as_str());
+        assert_eq!(response.headers()["content-length"], "10");
+        assert_eq!(response.body().as_ref(), &FIXTURE[..10]);
+
+        let response = fetch(&path, "GET", &[("range", "bytes=-5")]).await;
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.body().as_ref(), &FIXTURE[len - 5..]);
+    }
+
+    #[tokio::test]
+    async fn ranges_only_apply_to_the_version_in_if_range() {
+        let dir = TempDir::new();
+        let path = dir.write("resume.pdf", FIXTURE);
+        let etag = fetch(&path, "GET", &[]).await.headers()["etag"].to_str().unwrap().to_string();
+
+        let current = fetch(&path, "GET", &[("range", "bytes=0-9"), ("if-range", &etag)]).await;
+        assert_eq!(current.status(), StatusCode::PARTIAL_CONTENT);
+        let changed = fetch(&path, "GET", &[("range", "bytes=0-9"), ("if-range", "\"older\"")]).await;
+        assert_eq!(changed.status(), StatusCode::OK);
+        assert_eq!(changed.body().as_ref(), FIXTURE);
+    }
+
+    #[tokio::test]
+    async fn ranges_past_the_end_get_416() {
+        let dir = TempDir::new();
+        let path = dir.write("resume.pdf", FIXTURE);
+        let len = FIXTURE.len();
+
+        let response = fetch(&path, "GET", &[("range", &format!("bytes={}-", len))]).await;
+        assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(response.headers()["content-range"], format!("bytes */{}", len).as_str());
+        assert_eq!(json(response.body())["error"], "Requested range not satisfiable");
+    }
+
+    #[tokio::test]
+    async fn missing_file_download_is_not_found() {
+        let dir = TempDir::new();
+        let response = fetch(&dir.path("missing.pdf"), "GET", &[]).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(json(response.body())["error"], "Resume not found");
+    }
+}
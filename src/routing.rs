@@ -0,0 +1,332 @@
+use chrono::{DateTime, Datelike, NaiveDate, Timelike, Utc, Weekday};
+use std::env;
+
+// A weekly window, e.g. Mon-Fri 18-24, during which other recipients get
+// the notification. Hours are [start, end) in the schedule's timezone.
+#[derive(Debug)]
+struct RoutingRule {
+    name: String,
+    days: [bool; 7],
+    start_hour: u32,
+    end_hour: u32,
+    recipients: Vec<String>,
+}
+
+impl RoutingRule {
+    fn matches(&self, weekday: Weekday, hour: u32) -> bool {
+        self.days[weekday.num_days_from_monday() as usize] && hour >= self.start_hour && hour < self.end_hour
+    }
+
+    fn overlaps(&self, other: &RoutingRule) -> bool {
+        let shares_day = self.days.iter().zip(other.days.iter()).any(|(a, b)| *a && *b);
+        shares_day && self.start_hour < other.end_hour && other.start_hour < self.end_hour
+    }
+}
+
+// Date range, inclusive, that overrides every weekly rule
+#[derive(Debug)]
+struct Vacation {
+    from: NaiveDate,
+    until: NaiveDate,
+    recipients: Vec<String>,
+}
+
+// Which recipients a submission was routed to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Route {
+    Default,
+    Rule(usize),
+    Vacation,
+}
+
+// Notification recipients by time of day, from ROUTING_RULES, ROUTING_VACATION
+// and ROUTING_TIMEZONE. Submissions outside every rule go to the default
+// recipient.
+#[derive(Debug)]
+pub struct RoutingSchedule {
+    timezone: chrono_tz::Tz,
+    rules: Vec<RoutingRule>,
+    vacation: Option<Vacation>,
+}
+
+impl RoutingSchedule {
+    // Format:
+    //   ROUTING_RULES="evenings=Mon-Fri 18-24=>partner@example.com;weekend=Sat-Sun 0-24=>partner@example.com,me@example.com"
+    //   ROUTING_VACATION="2026-11-01..2026-11-15=>partner@example.com"
+    // Invalid or overlapping rules are a startup error rather than being skipped,
+    // so messages never silently go to the wrong person.
+    pub fn from_env() -> Result<Self, anyhow::Error> {
+        Self::parse(
+            &env::var("ROUTING_TIMEZONE").unwrap_or_default(),
+            &env::var("ROUTING_RULES").unwrap_or_default(),
+            &env::var("ROUTING_VACATION").unwrap_or_default(),
+        )
+    }
+
+    // The schedule from the three settings' values; empty means not set
+    pub fn parse(timezone: &str, rules_config: &str, vacation: &str) -> Result<Self, anyhow::Error> {
+        let timezone = match timezone.trim() {
+            "" => chrono_tz::UTC,
+            name => name
+                .parse::<chrono_tz::Tz>()
+                .map_err(|_| anyhow::anyhow!("Unknown ROUTING_TIMEZONE '{}'", name))?,
+        };
+
+        let mut rules: Vec<RoutingRule> = Vec::new();
+        for entry in rules_config.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+            let rule = parse_rule(entry).ok_or_else(|| anyhow::anyhow!("Invalid ROUTING_RULES entry: {}", entry))?;
+            if let Some(existing) = rules.iter().find(|existing| existing.overlaps(&rule)) {
+                return Err(anyhow::anyhow!(
+                    "ROUTING_RULES entries '{}' and '{}' overlap",
+                    existing.name,
+                    rule.name
+                ));
+            }
+            rules.push(rule);
+        }
+
+        let vacation = match vacation.trim() {
+            "" => None,
+            entry => Some(parse_vacation(entry).ok_or_else(|| anyhow::anyhow!("Invalid ROUTING_VACATION: {}", entry))?),
+        };
+
+        Ok(RoutingSchedule { timezone, rules, vacation })
+    }
+
+    pub fn route(&self, at: DateTime<Utc>) -> Route {
+        let local = at.with_timezone(&self.timezone);
+
+        if let Some(vacation) = &self.vacation {
+            if local.date_naive() >= vacation.from && local.date_naive() <= vacation.until {
+                return Route::Vacation;
+            }
+        }
+
+        self.rules
+            .iter()
+            .position(|rule| rule.matches(local.weekday(), local.hour()))
+            .map(Route::Rule)
+            .unwrap_or(Route::Default)
+    }
+
+    // Recipients for a route, or None for the default recipient
+    pub fn recipients(&self, route: Route) -> Option<&[String]> {
+        match route {
+            Route::Default => None,
+            Route::Rule(index) => self.rules.get(index).map(|rule| rule.recipients.as_slice()),
+            Route::Vacation => self.vacation.as_ref().map(|vacation| vacation.recipients.as_slice()),
+        }
+    }
+
+    pub fn label(&self, route: Route) -> &str {
+        match route {
+            Route::Default => "default",
+            Route::Rule(index) => self.rules.get(index).map(|rule| rule.name.as_str()).unwrap_or("default"),
+            Route::Vacation => "vacation",
+        }
+    }
+
+    pub fn rule_count(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn has_vacation(&self) -> bool {
+        self.vacation.is_some()
+    }
+}
+
+fn parse_rule(entry: &str) -> Option<RoutingRule> {
+    let (name, rest) = entry.split_once('=')?;
+    let (window, recipients) = rest.split_once("=>")?;
+    let (days, hours) = window.trim().split_once(' ')?;
+    let (start_hour, end_hour) = hours.trim().split_once('-')?;
+    let start_hour = start_hour.trim().parse::<u32>().ok()?;
+    let end_hour = end_hour.trim().parse::<u32>().ok()?;
+
+    if start_hour >= end_hour || end_hour > 24 || name.trim().is_empty() {
+        return None;
+    }
+
+    Some(RoutingRule {
+        name: name.trim().to_string(),
+        days: parse_days(days)?,
+        start_hour,
+        end_hour,
+        recipients: parse_recipients(recipients)?,
+    })
+}
+
+// "Mon-Fri", "Sat,Sun" or "Fri-Mon" (ranges wrap around the week)
+fn parse_days(spec: &str) -> Option<[bool; 7]> {
+    let mut days = [false; 7];
+    for part in spec.split(',').map(str::trim) {
+        match part.split_once('-') {
+            Some((from, to)) => {
+                let from = from.trim().parse::<Weekday>().ok()?;
+                let to = to.trim().parse::<Weekday>().ok()?;
+                let mut day = from;
+                loop {
+                    days[day.num_days_from_monday() as usize] = true;
+                    if day == to {
+                        break;
+                    }
+                    day = day.succ();
+                }
+            }
+            None => days[part.parse::<Weekday>().ok()?.num_days_from_monday() as usize] = true,
+        }
+    }
+    Some(days)
+}
+
+fn parse_vacation(entry: &str) -> Option<Vacation> {
+    let (dates, recipients) = entry.split_once("=>")?;
+    let (from, until) = dates.trim().split_once("..")?;
+    let from = NaiveDate::parse_from_str(from.trim(), "%Y-%m-%d").ok()?;
+    let until = NaiveDate::parse_from_str(until.trim(), "%Y-%m-%d").ok()?;
+
+    if from > until {
+        return None;
+    }
+
+    Some(Vacation {
+        from,
+        until,
+        recipients: parse_recipients(recipients)?,
+    })
+}
+
+fn parse_recipients(list: &str) -> Option<Vec<String>> {
+    let recipients: Vec<String> = list
+        .split(',')
+        .map(str::trim)
+        .filter(|email| !email.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    if recipients.is_empty() || recipients.iter().any(|email| !validator::validate_email(email)) {
+        return None;
+    }
+    Some(recipients)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    fn labels(schedule: &RoutingSchedule, times: &[DateTime<Utc>]) -> Vec<String> {
+        times.iter().map(|at| schedule.label(schedule.route(*at)).to_string()).collect()
+    }
+
+    #[test]
+    fn hours_are_start_inclusive_and_end_exclusive() {
+        let schedule = RoutingSchedule::parse("", "evenings=Mon-Fri 18-24=>partner@example.com", "").unwrap();
+        // 2026-03-02 is a Monday
+        assert_eq!(
+            labels(
+                &schedule,
+                &[utc(2026, 3, 2, 17, 59), utc(2026, 3, 2, 18, 0), utc(2026, 3, 2, 23, 59), utc(2026, 3, 3, 0, 0)]
+            ),
+            ["default", "evenings", "evenings", "default"]
+        );
+        assert_eq!(schedule.recipients(Route::Rule(0)), Some(&["partner@example.com".to_string()][..]));
+        assert_eq!(schedule.recipients(Route::Default), None);
+    }
+
+    #[test]
+    fn day_ranges_wrap_around_the_week() {
+        let schedule = RoutingSchedule::parse("", "long-weekend=Fri-Mon 0-24=>partner@example.com", "").unwrap();
+        // Thursday 2026-03-05 through Tuesday 2026-03-10
+        let days: Vec<_> = (5..=10).map(|day| utc(2026, 3, day, 12, 0)).collect();
+        assert_eq!(
+            labels(&schedule, &days),
+            ["default", "long-weekend", "long-weekend", "long-weekend", "long-weekend", "default"]
+        );
+    }
+
+    #[test]
+    fn rules_apply_in_the_schedule_timezone() {
+        let schedule =
+            RoutingSchedule::parse("America/New_York", "mornings=Mon-Fri 9-12=>partner@example.com", "").unwrap();
+        // 9:00 in New York is 14:00 UTC in winter and 13:00 UTC once DST starts (2026-03-08)
+        assert_eq!(labels(&schedule, &[utc(2026, 3, 6, 13, 30), utc(2026, 3, 6, 14, 0)]), ["default", "mornings"]);
+        assert_eq!(labels(&schedule, &[utc(2026, 3, 9, 12, 30), utc(2026, 3, 9, 13, 0)]), ["default", "mornings"]);
+
+        // Saturday 01:00 UTC is still Friday 20:00 in New York
+        let schedule = RoutingSchedule::parse("America/New_York", "late=Fri 20-21=>partner@example.com", "").unwrap();
+        assert_eq!(labels(&schedule, &[utc(2026, 3, 7, 1, 0)]), ["late"]);
+    }
+
+    #[test]
+    fn vacation_overrides_rules_on_its_local_dates() {
+        let schedule = RoutingSchedule::parse(
+            "America/New_York",
+            "weekend=Sat-Sun 0-24=>partner@example.com",
+            "2026-11-01..2026-11-15=>holiday@example.com, partner@example.com",
+        )
+        .unwrap();
+        assert!(schedule.has_vacation());
+        assert_eq!(
+            labels(
+                &schedule,
+                &[
+                    // 2026-10-31 23:30 in New York, a Saturday before the vacation
+                    utc(2026, 11, 1, 3, 30),
+                    utc(2026, 11, 1, 4, 30),
+                    utc(2026, 11, 15, 23, 0),
+                    // 2026-11-16 00:30 in New York
+                    utc(2026, 11, 16, 5, 30),
+                ]
+            ),
+            ["weekend", "vacation", "vacation", "default"]
+        );
+        assert_eq!(
+            schedule.recipients(Route::Vacation),
+            Some(&["holiday@example.com".to_string(), "partner@example.com".to_string()][..])
+        );
+    }
+
+    #[test]
+    fn overlapping_rules_are_rejected() {
+        let overlap = RoutingSchedule::parse("", "a=Mon-Fri 9-17=>a@example.com;b=Fri 16-20=>b@example.com", "");
+        assert_eq!(overlap.unwrap_err().to_string(), "ROUTING_RULES entries 'a' and 'b' overlap");
+
+        let wrapped = RoutingSchedule::parse("", "a=Fri-Mon 0-24=>a@example.com;b=Sun 10-11=>b@example.com", "");
+        assert!(wrapped.is_err());
+
+        // Touching windows and separate days are fine
+        let schedule = RoutingSchedule::parse(
+            "",
+            "day=Mon-Fri 9-17=>a@example.com;evening=Mon-Fri 17-24=>b@example.com;weekend=Sat,Sun 9-17=>c@example.com",
+            "",
+        )
+        .unwrap();
+        assert_eq!(schedule.rule_count(), 3);
+    }
+
+    #[test]
+    fn invalid_settings_are_startup_errors() {
+        for rules in [
+            "a=Mon-Fri 17-9=>a@example.com",
+            "a=Mon-Fri 9-25=>a@example.com",
+            "a=Funday 9-17=>a@example.com",
+            "a=Mon 9-17=>not-an-email",
+            "=Mon 9-17=>a@example.com",
+            "a=Mon 9-17",
+        ] {
+            assert!(RoutingSchedule::parse("", rules, "").is_err(), "{} should be rejected", rules);
+        }
+        assert!(RoutingSchedule::parse("", "", "2026-11-15..2026-11-01=>a@example.com").is_err());
+        assert!(RoutingSchedule::parse("", "", "2026-11-01..2026-11-15=>").is_err());
+        assert_eq!(
+            RoutingSchedule::parse("Mars/Olympus", "", "").unwrap_err().to_string(),
+            "Unknown ROUTING_TIMEZONE 'Mars/Olympus'"
+        );
+        assert!(!RoutingSchedule::parse("", "", "").unwrap().has_vacation());
+    }
+}
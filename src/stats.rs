@@ -0,0 +1,305 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use warp::Filter;
+
+use crate::query::{self, NoParams, QueryMode, ValidatedQuery};
+
+// Distinct attribution utmSource values tracked before the rest are lumped into "other"
+const MAX_UTM_SOURCES: usize = 50;
+
+// Contacts a breakdown key needs before it is published, by default
+const DEFAULT_MIN_COUNT: u64 = 20;
+
+static MESSAGES_RECEIVED: AtomicU64 = AtomicU64::new(0);
+static RESUME_DOWNLOADS: AtomicU64 = AtomicU64::new(0);
+static CONTACTS_BY_UTM_SOURCE: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+static CONTACTS_BY_COUNTRY: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+// Bounded by the CONTACT_FORM_VARIANTS allowlist, which is checked first
+static CONTACTS_BY_VARIANT: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+
+pub fn record_message_received() {
+    MESSAGES_RECEIVED.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn record_resume_download() {
+    RESUME_DOWNLOADS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn record_utm_source(source: Option<&str>) {
+    let mut sources = CONTACTS_BY_UTM_SOURCE.lock().unwrap();
+    let key = match source.map(str::to_lowercase) {
+        Some(source) if sources.contains_key(&source) || sources.len() < MAX_UTM_SOURCES => source,
+        Some(_) => "other".to_string(),
+        None => "none".to_string(),
+    };
+    *sources.entry(key).or_insert(0) += 1;
+}
+
+// Country codes come from the GeoIP database, so the set is naturally bounded
+pub fn record_country(country_code: Option<&str>) {
+    let key = country_code.unwrap_or("unknown").to_string();
+    *CONTACTS_BY_COUNTRY.lock().unwrap().entry(key).or_insert(0) += 1;
+}
+
+pub fn record_variant(variant: Option<&str>) {
+    let key = variant.unwrap_or("none").to_string();
+    *CONTACTS_BY_VARIANT.lock().unwrap().entry(key).or_insert(0) += 1;
+}
+
+// Exact submission counts per form variant, for the admin endpoint only
+pub fn contacts_by_variant() -> BTreeMap<String, u64> {
+    CONTACTS_BY_VARIANT.lock().unwrap().clone()
+}
+
+// The only metrics that can ever appear on the public stats endpoint.
+// Anything not listed here has no way to be exposed, whatever the config says.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PublicMetric {
+    MessagesReceived,
+    ResumeDownloads,
+    UptimeDays,
+    BuildDate,
+    ContactsByUtmSource,
+    ContactsByCountry,
+}
+
+impl PublicMetric {
+    const ALL: [PublicMetric; 6] = [
+        PublicMetric::MessagesReceived,
+        PublicMetric::ResumeDownloads,
+        PublicMetric::UptimeDays,
+        PublicMetric::BuildDate,
+        PublicMetric::ContactsByUtmSource,
+        PublicMetric::ContactsByCountry,
+    ];
+
+    // Exposed when PUBLIC_STATS is unset; the breakdowns are opt-in
+    const DEFAULT: [PublicMetric; 4] = [
+        PublicMetric::MessagesReceived,
+        PublicMetric::ResumeDownloads,
+        PublicMetric::UptimeDays,
+        PublicMetric::BuildDate,
+    ];
+
+    fn key(self) -> &'static str {
+        match self {
+            PublicMetric::MessagesReceived => "messagesReceived",
+            PublicMetric::ResumeDownloads => "resumeDownloads",
+            PublicMetric::UptimeDays => "uptimeDays",
+            PublicMetric::BuildDate => "buildDate",
+            PublicMetric::ContactsByUtmSource => "contactsByUtmSource",
+            PublicMetric::ContactsByCountry => "contactsByCountry",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|metric| metric.key() == key)
+    }
+}
+
+pub struct PublicStats {
+    metrics: Vec<PublicMetric>,
+    rounding: u64,
+    min_count: u64,
+    started_at: Instant,
+}
+
+impl PublicStats {
+    // PUBLIC_STATS picks a subset of PublicMetric by key (default: PublicMetric::DEFAULT),
+    // PUBLIC_STATS_ROUNDING rounds counts down to a multiple (default: 10),
+    // PUBLIC_STATS_MIN_COUNT hides breakdown keys with fewer contacts (default: 20)
+    pub fn from_env() -> Self {
+        Self::parse(
+            &env::var("PUBLIC_STATS").unwrap_or_default(),
+            &env::var("PUBLIC_STATS_ROUNDING").unwrap_or_default(),
+            &env::var("PUBLIC_STATS_MIN_COUNT").unwrap_or_default(),
+        )
+    }
+
+    pub fn parse(metrics: &str, rounding: &str, min_count: &str) -> Self {
+        let metrics = match metrics.trim() {
+            "" => PublicMetric::DEFAULT.to_vec(),
+            list => list
+                .split(',')
+                .map(str::trim)
+                .filter_map(|key| {
+                    let metric = PublicMetric::from_key(key);
+                    if metric.is_none() {
+                        tracing::warn!("Ignoring unknown PUBLIC_STATS metric: {}", key);
+                    }
+                    metric
+                })
+                .collect(),
+        };
+
+        let rounding = rounding.trim().parse::<u64>().ok().filter(|v| *v > 0).unwrap_or(10);
+        let min_count = match min_count.trim() {
+            "" => DEFAULT_MIN_COUNT,
+            value => value.parse::<u64>().unwrap_or_else(|_| {
+                tracing::warn!("Invalid PUBLIC_STATS_MIN_COUNT '{}', using {}", value, DEFAULT_MIN_COUNT);
+                DEFAULT_MIN_COUNT
+            }),
+        };
+
+        PublicStats {
+            metrics,
+            rounding,
+            min_count,
+            started_at: Instant::now(),
+        }
+    }
+
+    pub fn snapshot(&self) -> serde_json::Value {
+        let mut stats = serde_json::Map::new();
+
+        for metric in &self.metrics {
+            let value = match metric {
+                PublicMetric::MessagesReceived => {
+                    self.round(MESSAGES_RECEIVED.load(Ordering::Relaxed)).into()
+                }
+                PublicMetric::ResumeDownloads => {
+                    self.round(RESUME_DOWNLOADS.load(Ordering::Relaxed)).into()
+                }
+                PublicMetric::UptimeDays => (self.started_at.elapsed().as_secs() / 86_400).into(),
+                PublicMetric::BuildDate => match option_env!("BUILD_DATE") {
+                    Some(date) => date.into(),
+                    None => continue,
+                },
+                PublicMetric::ContactsByUtmSource => self.breakdown(&CONTACTS_BY_UTM_SOURCE),
+                PublicMetric::ContactsByCountry => self.breakdown(&CONTACTS_BY_COUNTRY),
+            };
+            stats.insert(metric.key().to_string(), value);
+        }
+
+        serde_json::Value::Object(stats)
+    }
+
+    // Keys are chosen by whoever submits the form, so one only shows up once
+    // enough contacts share it that it can't single anyone out
+    fn breakdown(&self, counts: &Mutex<BTreeMap<String, u64>>) -> serde_json::Value {
+        counts
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, count)| **count >= self.min_count && self.round(**count) > 0)
+            .map(|(key, count)| (key.clone(), self.round(*count).into()))
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+
+    fn round(&self, count: u64) -> u64 {
+        count / self.rounding * self.rounding
+    }
+}
+
+// GET /api/stats/public - Coarse aggregates for the site footer. The query
+// string plays no part in what is returned.
+pub fn route(
+    stats: Arc<PublicStats>,
+    mode: QueryMode,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("api" / "stats" / "public")
+        .and(warp::get())
+        .and(query::validated::<NoParams>(mode))
+        .map(move |query: ValidatedQuery<NoParams>| query.reply(warp::reply::json(&stats.snapshot())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn keys(stats: PublicStats, path: &str) -> Vec<String> {
+        let response = warp::test::request().path(path).reply(&route(Arc::new(stats), QueryMode::Lenient)).await;
+        assert_eq!(response.status(), warp::http::StatusCode::OK);
+        let body: serde_json::Map<String, serde_json::Value> = serde_json::from_slice(response.body()).unwrap();
+        body.keys().cloned().collect()
+    }
+
+    #[tokio::test]
+    async fn only_allowlisted_metrics_are_exposed() {
+        let stats = PublicStats::parse("messagesReceived, contactsByVariant, rejectedOrigins, adminErrors", "", "");
+        assert_eq!(keys(stats, "/api/stats/public").await, ["messagesReceived"]);
+    }
+
+    #[tokio::test]
+    async fn query_parameters_cannot_add_metrics() {
+        for query in [
+            "?metric=contactsByVariant",
+            "?metrics=contactsByUtmSource,contactsByCountry",
+            "?include=all&contactsByVariant=true",
+            "?PUBLIC_STATS=contactsByVariant",
+        ] {
+            // The default set; buildDate only appears when it was set at build time
+            let mut expected = vec!["messagesReceived", "resumeDownloads", "uptimeDays"];
+            if option_env!("BUILD_DATE").is_some() {
+                expected.insert(0, "buildDate");
+            }
+            let stats = PublicStats::parse("", "", "");
+            assert_eq!(keys(stats, &format!("/api/stats/public{}", query)).await, expected, "{}", query);
+        }
+    }
+
+    #[test]
+    fn counts_are_rounded_down() {
+        let stats = PublicStats::parse("", "25", "");
+        assert_eq!(stats.round(24), 0);
+        assert_eq!(stats.round(74), 50);
+        assert_eq!(PublicStats::parse("", "0", "").round(19), 10);
+        assert_eq!(PublicStats::parse("", "ten", "").round(19), 10);
+    }
+
+    fn counts(entries: &[(&str, u64)]) -> Mutex<BTreeMap<String, u64>> {
+        Mutex::new(entries.iter().map(|(key, count)| (key.to_string(), *count)).collect())
+    }
+
+    #[test]
+    fn breakdown_hides_rare_keys() {
+        let counts = counts(&[("linkedin", 47), ("none", 20), ("<script>", 3), ("my-name-here", 19)]);
+
+        let stats = PublicStats::parse("", "", "");
+        assert_eq!(stats.breakdown(&counts), serde_json::json!({ "linkedin": 40, "none": 20 }));
+
+        let stats = PublicStats::parse("", "", "30");
+        assert_eq!(stats.breakdown(&counts), serde_json::json!({ "linkedin": 40 }));
+
+        // Without a minimum, keys still need a non-zero rounded count
+        let stats = PublicStats::parse("", "25", "0");
+        assert_eq!(stats.breakdown(&counts), serde_json::json!({ "linkedin": 25 }));
+    }
+
+    #[tokio::test]
+    async fn country_breakdown_has_the_same_threshold() {
+        // User-assigned codes, so no other test's submissions land in them
+        for _ in 0..25 {
+            record_country(Some("XA"));
+        }
+        for _ in 0..3 {
+            record_country(Some("XB"));
+        }
+
+        let stats = Arc::new(PublicStats::parse("contactsByCountry", "", ""));
+        let response = warp::test::request().path("/api/stats/public").reply(&route(stats, QueryMode::Lenient)).await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["contactsByCountry"]["XA"], 20);
+        assert!(body["contactsByCountry"].get("XB").is_none());
+    }
+
+    #[tokio::test]
+    async fn unknown_parameters_warn_or_fail_by_mode() {
+        let request = || warp::test::request().path("/api/stats/public?metric=contactsByVariant");
+
+        let lenient = request().reply(&route(Arc::new(PublicStats::parse("", "", "")), QueryMode::Lenient)).await;
+        assert_eq!(lenient.status(), warp::http::StatusCode::OK);
+        assert_eq!(lenient.headers()["warning"], "299 - \"unknown query parameter: metric\"");
+
+        let strict = route(Arc::new(PublicStats::parse("", "", "")), QueryMode::Strict)
+            .recover(crate::admin::handle_rejection);
+        let strict = request().reply(&strict).await;
+        assert_eq!(strict.status(), warp::http::StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = serde_json::from_slice(strict.body()).unwrap();
+        assert_eq!(body, serde_json::json!({ "error": "unknown query parameter: metric", "validParameters": {} }));
+    }
+}
@@ -0,0 +1,574 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use personal_api::models::{
+    Attribution, ContactForm, ErrorMessage, GeoLocation, RetrySummary, Submission, SubmissionList, SubmissionStatus,
+};
+use personal_api::{sanitize_input, sanitize_multiline};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+use warp::Reply;
+
+use crate::brevo::BrevoMailer;
+use crate::query::QueryParams;
+
+const DEFAULT_DB_PATH: &str = "data/contacts.db";
+
+// Submissions listed when no limit is given, and the most one request can ask for
+const DEFAULT_LIST_LIMIT: u32 = 100;
+const MAX_LIST_LIMIT: u32 = 500;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS submissions (
+    id TEXT PRIMARY KEY,
+    request_id TEXT NOT NULL,
+    received_at TEXT NOT NULL,
+    email TEXT NOT NULL,
+    first_name TEXT NOT NULL,
+    last_name TEXT NOT NULL,
+    phone_number TEXT NOT NULL,
+    message TEXT NOT NULL,
+    attribution TEXT,
+    variant TEXT,
+    country_code TEXT,
+    city TEXT,
+    status TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT
+);
+CREATE INDEX IF NOT EXISTS submissions_status ON submissions (status, received_at);
+";
+
+const COLUMNS: &str = "id, request_id, received_at, email, first_name, last_name, phone_number, message, \
+     attribution, variant, country_code, city, status, attempts, last_error";
+
+// Every contact submission, written before the notification email is sent so
+// a message survives Brevo being down. One connection for the whole process;
+// queries run on the blocking pool.
+pub struct SubmissionStore {
+    path: String,
+    conn: Arc<Mutex<Connection>>,
+    // Held for a whole retry run so two runs never send the same row twice
+    retry_lock: tokio::sync::Mutex<()>,
+}
+
+impl SubmissionStore {
+    // Open CONTACT_DB_PATH
+    pub fn open_from_env() -> Result<Self, anyhow::Error> {
+        let path = env::var("CONTACT_DB_PATH")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| DEFAULT_DB_PATH.to_string());
+        Self::open(&path)
+    }
+
+    // Open the database at `path`, creating the file and its directory if needed
+    pub fn open(path: &str) -> Result<Self, anyhow::Error> {
+        if let Some(dir) = Path::new(&path).parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|e| anyhow::anyhow!("Could not create {}: {}", dir.display(), e))?;
+        }
+        let conn = Connection::open(path).map_err(|e| anyhow::anyhow!("Could not open {}: {}", path, e))?;
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| anyhow::anyhow!("Could not create the schema in {}: {}", path, e))?;
+
+        // Rows still pending at startup were interrupted mid-send
+        let interrupted = conn.execute(
+            "UPDATE submissions SET status = 'failed', last_error = ?1 WHERE status = 'pending'",
+            params!["interrupted before the email was sent"],
+        )?;
+        if interrupted > 0 {
+            tracing::warn!("Marked {} interrupted contact submissions as failed", interrupted);
+        }
+
+        tracing::info!("Storing contact submissions in {}", path);
+        Ok(SubmissionStore {
+            path: path.to_string(),
+            conn: Arc::new(Mutex::new(conn)),
+            retry_lock: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    // The data directory takes new files and SQLite finds no corruption
+    pub async fn check_integrity(&self) -> Result<(), anyhow::Error> {
+        let dir = match Path::new(&self.path).parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            Some(dir) => dir.to_path_buf(),
+            None => Path::new(".").to_path_buf(),
+        };
+        let probe = dir.join(".write-check");
+        fs::write(&probe, b"")
+            .and_then(|()| fs::remove_file(&probe))
+            .map_err(|e| anyhow::anyhow!("{} is not writable: {}", dir.display(), e))?;
+
+        let result: String = self.run(|conn| conn.query_row("PRAGMA quick_check", [], |row| row.get(0))).await?;
+        if result != "ok" {
+            return Err(anyhow::anyhow!("{} failed PRAGMA quick_check: {}", self.path, result));
+        }
+        Ok(())
+    }
+
+    async fn run<T, F>(&self, query: F) -> Result<T, anyhow::Error>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> rusqlite::Result<T> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+        // A query that panicked leaves the connection itself usable
+        tokio::task::spawn_blocking(move || query(&conn.lock().unwrap_or_else(PoisonError::into_inner)))
+            .await
+            .map_err(|e| anyhow::anyhow!("Database task failed: {}", e))?
+            .map_err(anyhow::Error::from)
+    }
+
+    // Store a new submission as pending, with sanitized fields; the message keeps its line breaks
+    pub async fn insert(
+        &self,
+        contact_id: &str,
+        request_id: &str,
+        form: &ContactForm,
+        received_at: DateTime<Utc>,
+    ) -> Result<(), anyhow::Error> {
+        let contact_id = contact_id.to_string();
+        let request_id = request_id.to_string();
+        let received_at = received_at.to_rfc3339_opts(SecondsFormat::Millis, true);
+        let email = sanitize_input(&form.email);
+        let first_name = sanitize_input(&form.first_name);
+        let last_name = sanitize_input(&form.last_name);
+        let phone_number = sanitize_input(&form.phone_number);
+        let message = sanitize_multiline(&form.message);
+        let attribution = form.attribution.as_ref().map(serde_json::to_string).transpose()?;
+        let variant = form.variant.clone();
+        let (country_code, city) = form.location.clone().map(|l| (l.country_code, l.city)).unwrap_or_default();
+
+        self.run(move |conn| {
+            conn.execute(
+                &format!(
+                    "INSERT INTO submissions ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, 'pending', 0, NULL)",
+                    COLUMNS
+                ),
+                params![
+                    contact_id,
+                    request_id,
+                    received_at,
+                    email,
+                    first_name,
+                    last_name,
+                    phone_number,
+                    message,
+                    attribution,
+                    variant,
+                    country_code,
+                    city,
+                ],
+            )
+        })
+        .await?;
+        Ok(())
+    }
+
+    // Record the outcome of an email attempt
+    pub async fn record_delivery(&self, contact_id: &str, error: Option<String>) -> Result<(), anyhow::Error> {
+        let contact_id = contact_id.to_string();
+        let status = if error.is_some() { "failed" } else { "sent" };
+        self.run(move |conn| {
+            conn.execute(
+                "UPDATE submissions SET status = ?1, attempts = attempts + 1, last_error = ?2 WHERE id = ?3",
+                params![status, error, contact_id],
+            )
+        })
+        .await?;
+        Ok(())
+    }
+
+    // Newest first, optionally only one status
+    pub async fn list(&self, status: Option<SubmissionStatus>, limit: u32) -> Result<Vec<Submission>, anyhow::Error> {
+        self.run(move |conn| {
+            let mut statement = conn.prepare(&format!(
+                "SELECT {} FROM submissions WHERE ?1 IS NULL OR status = ?1 ORDER BY received_at DESC LIMIT ?2",
+                COLUMNS
+            ))?;
+            let rows = statement.query_map(params![status.map(status_name), limit], submission_from_row)?;
+            rows.collect()
+        })
+        .await
+    }
+
+    pub async fn get(&self, contact_id: &str) -> Result<Option<Submission>, anyhow::Error> {
+        let contact_id = contact_id.to_string();
+        self.run(move |conn| {
+            conn.query_row(
+                &format!("SELECT {} FROM submissions WHERE id = ?1", COLUMNS),
+                params![contact_id],
+                submission_from_row,
+            )
+            .optional()
+        })
+        .await
+    }
+
+    // Re-send the notification email for every failed submission, oldest first
+    pub async fn retry_failed(&self, mailer: &BrevoMailer) -> Result<RetrySummary, anyhow::Error> {
+        let _running = self.retry_lock.lock().await;
+        let failed = self
+            .run(|conn| {
+                let mut statement = conn.prepare(&format!(
+                    "SELECT {} FROM submissions WHERE status = 'failed' ORDER BY received_at",
+                    COLUMNS
+                ))?;
+                let rows = statement.query_map([], submission_from_row)?;
+                rows.collect::<rusqlite::Result<Vec<_>>>()
+            })
+            .await?;
+
+        let mut summary = RetrySummary::default();
+        for submission in failed {
+            let received_at = DateTime::parse_from_rfc3339(&submission.received_at)
+                .map(|t| t.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now());
+
+            summary.attempted += 1;
+            let error = match mailer.send(&form_from(&submission), &submission.id, received_at).await {
+                Ok(()) => {
+                    summary.sent += 1;
+                    tracing::info!("Resent contact form email for ID: {}", submission.id);
+                    None
+                }
+                Err(e) => {
+                    summary.failed += 1;
+                    tracing::warn!("Retry failed for contact ID {}: {:#}", submission.id, e);
+                    Some(format!("{:#}", e))
+                }
+            };
+            self.record_delivery(&submission.id, error).await?;
+        }
+
+        Ok(summary)
+    }
+}
+
+fn status_name(status: SubmissionStatus) -> &'static str {
+    match status {
+        SubmissionStatus::Pending => "pending",
+        SubmissionStatus::Sent => "sent",
+        SubmissionStatus::Failed => "failed",
+    }
+}
+
+fn submission_from_row(row: &Row<'_>) -> rusqlite::Result<Submission> {
+    let attribution: Option<String> = row.get("attribution")?;
+    let country_code: Option<String> = row.get("country_code")?;
+    let city: Option<String> = row.get("city")?;
+    let status: String = row.get("status")?;
+
+    Ok(Submission {
+        id: row.get("id")?,
+        request_id: row.get("request_id")?,
+        received_at: row.get("received_at")?,
+        email: row.get("email")?,
+        first_name: row.get("first_name")?,
+        last_name: row.get("last_name")?,
+        phone_number: row.get("phone_number")?,
+        message: row.get("message")?,
+        attribution: attribution.and_then(|json| serde_json::from_str::<Attribution>(&json).ok()),
+        variant: row.get("variant")?,
+        location: (country_code.is_some() || city.is_some()).then_some(GeoLocation { country_code, city }),
+        status: match status.as_str() {
+            "sent" => SubmissionStatus::Sent,
+            "pending" => SubmissionStatus::Pending,
+            _ => SubmissionStatus::Failed,
+        },
+        attempts: row.get("attempts")?,
+        last_error: row.get("last_error")?,
+    })
+}
+
+// The form a stored submission was received as, for re-sending its email
+fn form_from(submission: &Submission) -> ContactForm {
+    ContactForm {
+        email: submission.email.clone(),
+        first_name: submission.first_name.clone(),
+        last_name: submission.last_name.clone(),
+        phone_number: submission.phone_number.clone(),
+        message: submission.message.clone(),
+        attribution: submission.attribution.clone(),
+        variant: submission.variant.clone(),
+        website: None,
+        location: submission.location.clone(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SubmissionsQuery {
+    status: Option<SubmissionStatus>,
+    limit: Option<u32>,
+}
+
+impl QueryParams for SubmissionsQuery {
+    const PARAMS: &'static [(&'static str, &'static str)] =
+        &[("status", "one of pending, sent, failed"), ("limit", "integer, at most 500")];
+}
+
+fn unavailable() -> warp::reply::Response {
+    warp::reply::with_status(
+        warp::reply::json(&ErrorMessage::new("submission storage is unavailable")),
+        warp::http::StatusCode::SERVICE_UNAVAILABLE,
+    )
+    .into_response()
+}
+
+fn database_error(e: anyhow::Error) -> warp::reply::Response {
+    tracing::error!("Contact submission query failed: {:#}", e);
+    warp::reply::with_status(
+        warp::reply::json(&ErrorMessage::new("database error")),
+        warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+    )
+    .into_response()
+}
+
+// GET /api/contact/submissions - Stored submissions, newest first
+pub async fn handle_list(
+    query: SubmissionsQuery,
+    store: Option<Arc<SubmissionStore>>,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    let Some(store) = store else {
+        return Ok(unavailable());
+    };
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIST_LIMIT).min(MAX_LIST_LIMIT);
+    Ok(match store.list(query.status, limit).await {
+        Ok(submissions) => warp::reply::json(&SubmissionList {
+            count: submissions.len(),
+            submissions,
+        })
+        .into_response(),
+        Err(e) => database_error(e),
+    })
+}
+
+// GET /api/contact/submissions/{id}
+pub async fn handle_get(
+    contact_id: String,
+    store: Option<Arc<SubmissionStore>>,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    let Some(store) = store else {
+        return Ok(unavailable());
+    };
+
+    Ok(match store.get(&contact_id).await {
+        Ok(Some(submission)) => warp::reply::json(&submission).into_response(),
+        Ok(None) => warp::reply::with_status(
+            warp::reply::json(&ErrorMessage::new("submission not found")),
+            warp::http::StatusCode::NOT_FOUND,
+        )
+        .into_response(),
+        Err(e) => database_error(e),
+    })
+}
+
+// POST /api/contact/retry-failed - Re-send the email for every failed submission
+pub async fn handle_retry(
+    store: Option<Arc<SubmissionStore>>,
+    mailer: Option<Arc<BrevoMailer>>,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    let Some(store) = store else {
+        return Ok(unavailable());
+    };
+    let Some(mailer) = mailer else {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&ErrorMessage::new("email is not configured")),
+            warp::http::StatusCode::SERVICE_UNAVAILABLE,
+        )
+        .into_response());
+    };
+
+    Ok(match store.retry_failed(&mailer).await {
+        Ok(summary) => warp::reply::json(&summary).into_response(),
+        Err(e) => database_error(e),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, TempDir};
+    use warp::http::StatusCode;
+
+    fn form(email: &str) -> ContactForm {
+        ContactForm {
+            email: email.to_string(),
+            first_name: " Jane\u{0}".to_string(),
+            last_name: "Doe".to_string(),
+            phone_number: "+1 555 0100 200".to_string(),
+            message: "Hello".to_string(),
+            variant: Some("short".to_string()),
+            location: Some(GeoLocation {
+                country_code: Some("GB".to_string()),
+                city: None,
+            }),
+            ..ContactForm::default()
+        }
+    }
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_770_000_000 + seconds, 0).unwrap()
+    }
+
+    async fn store_with(dir: &TempDir, rows: &[(&str, Option<&str>)]) -> SubmissionStore {
+        let store = SubmissionStore::open(&dir.path("db/contacts.db")).unwrap();
+        for (i, (id, error)) in rows.iter().enumerate() {
+            store.insert(id, "req-1", &form(&format!("{}@example.com", id)), at(i as i64)).await.unwrap();
+            store.record_delivery(id, error.map(str::to_string)).await.unwrap();
+        }
+        store
+    }
+
+    async fn json(response: warp::reply::Response) -> (StatusCode, serde_json::Value) {
+        let status = response.status();
+        let body = warp::hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn submissions_are_stored_sanitized_with_their_delivery() {
+        let dir = TempDir::new();
+        let store = store_with(&dir, &[("a", None), ("b", Some("Brevo returned 500"))]).await;
+
+        let a = store.get("a").await.unwrap().unwrap();
+        assert_eq!((a.first_name.as_str(), a.variant.as_deref()), ("Jane", Some("short")));
+        assert_eq!(a.location.unwrap().country_code.as_deref(), Some("GB"));
+        assert_eq!((a.status, a.attempts, a.last_error), (SubmissionStatus::Sent, 1, None));
+
+        let b = store.get("b").await.unwrap().unwrap();
+        assert_eq!((b.status, b.last_error.as_deref()), (SubmissionStatus::Failed, Some("Brevo returned 500")));
+        assert!(store.get("c").await.unwrap().is_none());
+
+        let newest_first: Vec<_> = store.list(None, 10).await.unwrap().into_iter().map(|s| s.id).collect();
+        assert_eq!(newest_first, ["b", "a"]);
+        assert_eq!(store.list(Some(SubmissionStatus::Failed), 10).await.unwrap().len(), 1);
+        assert_eq!(store.list(None, 1).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn pending_rows_are_failed_on_reopen() {
+        let dir = TempDir::new();
+        let store = SubmissionStore::open(&dir.path("contacts.db")).unwrap();
+        store.insert("a", "req-1", &form("a@example.com"), at(0)).await.unwrap();
+        assert_eq!(store.get("a").await.unwrap().unwrap().status, SubmissionStatus::Pending);
+        drop(store);
+
+        let store = SubmissionStore::open(&dir.path("contacts.db")).unwrap();
+        let a = store.get("a").await.unwrap().unwrap();
+        assert_eq!(a.status, SubmissionStatus::Failed);
+        assert_eq!(a.last_error.as_deref(), Some("interrupted before the email was sent"));
+    }
+
+    #[tokio::test]
+    async fn integrity_check_needs_a_writable_data_directory() {
+        let dir = TempDir::new();
+        let store = store_with(&dir, &[("a", None)]).await;
+        store.check_integrity().await.unwrap();
+
+        fs::remove_dir_all(dir.path("db")).unwrap();
+        let error = store.check_integrity().await.unwrap_err().to_string();
+        assert!(error.ends_with("is not writable: No such file or directory (os error 2)"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn a_panicked_query_does_not_poison_the_store() {
+        let dir = TempDir::new();
+        let store = store_with(&dir, &[("a", None)]).await;
+
+        let panicked = store.run(|_| -> rusqlite::Result<()> { panic!("query panicked") }).await;
+        assert!(panicked.is_err());
+        assert!(store.get("a").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn retry_resends_failed_rows_oldest_first() {
+        let dir = TempDir::new();
+        let store = store_with(&dir, &[("a", Some("down")), ("b", None), ("c", Some("down"))]).await;
+        let (brevo_url, payloads) = test_support::brevo_mock();
+
+        let summary = store.retry_failed(&test_support::brevo_mailer(&brevo_url)).await.unwrap();
+        assert_eq!((summary.attempted, summary.sent, summary.failed), (2, 2, 0));
+        let resent: Vec<_> = payloads.lock().unwrap().iter().map(|p| p["headers"]["X-Contact-Id"].clone()).collect();
+        assert_eq!(resent, ["a", "c"]);
+
+        let c = store.get("c").await.unwrap().unwrap();
+        assert_eq!((c.status, c.attempts, c.last_error), (SubmissionStatus::Sent, 2, None));
+        assert_eq!(store.get("b").await.unwrap().unwrap().attempts, 1);
+
+        // Nothing left to send
+        let summary = store.retry_failed(&test_support::brevo_mailer(&brevo_url)).await.unwrap();
+        assert_eq!(summary.attempted, 0);
+    }
+
+    #[tokio::test]
+    async fn multi_line_messages_keep_their_line_breaks() {
+        let dir = TempDir::new();
+        let store = SubmissionStore::open(&dir.path("contacts.db")).unwrap();
+        let message = ContactForm { message: " Hello,\r\n\tthanks\u{0}\n\nJane ".to_string(), ..form("a@example.com") };
+        store.insert("a", "req-1", &message, at(0)).await.unwrap();
+        store.record_delivery("a", Some("down".to_string())).await.unwrap();
+        assert_eq!(store.get("a").await.unwrap().unwrap().message, "Hello,\n\tthanks\n\nJane");
+
+        let (brevo_url, payloads) = test_support::brevo_mock();
+        store.retry_failed(&test_support::brevo_mailer(&brevo_url)).await.unwrap();
+        let html = payloads.lock().unwrap()[0]["htmlContent"].as_str().unwrap().to_string();
+        assert!(html.contains("<p>Hello,<br>\tthanks<br><br>Jane</p>"), "{}", html);
+    }
+
+    #[tokio::test]
+    async fn retry_keeps_rows_that_fail_again() {
+        let dir = TempDir::new();
+        let store = store_with(&dir, &[("a", Some("down"))]).await;
+
+        let summary = store.retry_failed(&test_support::brevo_mailer("http://127.0.0.1:9")).await.unwrap();
+        assert_eq!((summary.attempted, summary.sent, summary.failed), (1, 0, 1));
+        let a = store.get("a").await.unwrap().unwrap();
+        assert_eq!((a.status, a.attempts), (SubmissionStatus::Failed, 2));
+        assert_ne!(a.last_error.as_deref(), Some("down"));
+    }
+
+    #[tokio::test]
+    async fn handlers_list_get_and_retry() {
+        let dir = TempDir::new();
+        let store = Some(Arc::new(store_with(&dir, &[("a", None), ("b", Some("down"))]).await));
+
+        let query = SubmissionsQuery {
+            status: Some(SubmissionStatus::Failed),
+            limit: None,
+        };
+        let (status, body) = json(handle_list(query, store.clone()).await.unwrap()).await;
+        assert_eq!((status, body["count"].clone(), body["submissions"][0]["id"].clone()), (StatusCode::OK, 1.into(), "b".into()));
+
+        let (status, body) = json(handle_get("a".to_string(), store.clone()).await.unwrap()).await;
+        assert_eq!((status, body["status"].clone()), (StatusCode::OK, "sent".into()));
+        let (status, body) = json(handle_get("missing".to_string(), store.clone()).await.unwrap()).await;
+        assert_eq!((status, body), (StatusCode::NOT_FOUND, serde_json::json!({ "error": "submission not found" })));
+
+        let (status, body) = json(handle_retry(store.clone(), None).await.unwrap()).await;
+        assert_eq!((status, body), (StatusCode::SERVICE_UNAVAILABLE, serde_json::json!({ "error": "email is not configured" })));
+        let (brevo_url, _) = test_support::brevo_mock();
+        let mailer = Some(Arc::new(test_support::brevo_mailer(&brevo_url)));
+        let (status, body) = json(handle_retry(store, mailer).await.unwrap()).await;
+        assert_eq!((status, body), (StatusCode::OK, serde_json::json!({ "attempted": 1, "sent": 1, "failed": 0 })));
+    }
+
+    #[tokio::test]
+    async fn handlers_without_a_store_are_unavailable() {
+        let query = SubmissionsQuery { status: None, limit: None };
+        for response in [
+            handle_list(query, None).await.unwrap(),
+            handle_get("a".to_string(), None).await.unwrap(),
+            handle_retry(None, None).await.unwrap(),
+        ] {
+            let (status, body) = json(response).await;
+            assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+            assert_eq!(body["error"], "submission storage is unavailable");
+        }
+    }
+}
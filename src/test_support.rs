@@ -0,0 +1,88 @@
+// Helpers shared by the unit tests
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use warp::Filter;
+
+use crate::brevo::{BrevoConfig, BrevoMailer};
+use crate::routing::RoutingSchedule;
+
+// A fresh directory under the system temp dir, removed again on drop
+pub struct TempDir {
+    root: PathBuf,
+}
+
+impl TempDir {
+    pub fn new() -> Self {
+        let root = std::env::temp_dir().join(format!("personal-api-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).expect("create temp dir");
+        TempDir { root }
+    }
+
+    // Path of a file in the directory, which need not exist
+    pub fn path(&self, name: &str) -> String {
+        self.root.join(name).to_string_lossy().into_owned()
+    }
+
+    pub fn write(&self, name: &str, contents: &[u8]) -> String {
+        let path = self.path(name);
+        fs::write(&path, contents).expect("write temp file");
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+// Serve a filter on an ephemeral localhost port, returning its base URL
+pub fn serve<F>(filter: F) -> String
+where
+    F: warp::Filter + Clone + Send + Sync + 'static,
+    F::Extract: warp::Reply,
+{
+    let (addr, server) = warp::serve(filter).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+    format!("http://{}", addr)
+}
+
+// Mock Brevo send endpoint, recording each payload it accepts
+pub fn brevo_mock() -> (String, Arc<Mutex<Vec<serde_json::Value>>>) {
+    let payloads = Arc::new(Mutex::new(Vec::new()));
+    let seen = payloads.clone();
+    let url = serve(
+        warp::path!("smtp" / "email")
+            .and(warp::post())
+            .and(warp::header::exact("api-key", "brevo-key"))
+            .and(warp::body::json())
+            .map(move |payload: serde_json::Value| {
+                seen.lock().unwrap().push(payload);
+                warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({ "messageId": "<1@brevo>" })),
+                    warp::http::StatusCode::CREATED,
+                )
+            }),
+    );
+    (url, payloads)
+}
+
+// Brevo settings for a mock at `api_url`, with API key "brevo-key"
+pub fn brevo_config(api_url: &str) -> BrevoConfig {
+    BrevoConfig {
+        api_key: "brevo-key".to_string(),
+        sender_email: "site@example.com".to_string(),
+        sender_name: "Example Site".to_string(),
+        recipient_email: "me@example.com".to_string(),
+        site_key: None,
+        display_timezone: chrono_tz::UTC,
+        api_url: api_url.to_string(),
+    }
+}
+
+// A mailer without translation or routing rules, sending to `api_url`
+pub fn brevo_mailer(api_url: &str) -> BrevoMailer {
+    BrevoMailer::new(brevo_config(api_url), reqwest::Client::new(), None, RoutingSchedule::parse("", "", "").unwrap())
+        .unwrap()
+}
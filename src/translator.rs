@@ -0,0 +1,287 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+
+// Translation is best effort, so don't hold up the email for long
+const TRANSLATE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Result of translating a message into English
+#[derive(Debug)]
+pub struct Translation {
+    pub detected_language: String,
+    pub text: String,
+}
+
+// Machine translation provider used for the notification email gist
+#[async_trait]
+pub trait Translator: Send + Sync {
+    fn provider(&self) -> &str;
+
+    async fn translate_to_english(&self, text: &str) -> Result<Translation, anyhow::Error>;
+}
+
+// Build the configured translator, or None when translation is not set up
+pub fn from_env(client: Client) -> Option<Arc<dyn Translator>> {
+    let url = env::var("TRANSLATOR_URL").ok().filter(|v| !v.trim().is_empty())?;
+    let api_key = env::var("TRANSLATOR_API_KEY").ok().filter(|v| !v.trim().is_empty());
+    let provider = env::var("TRANSLATOR_PROVIDER").unwrap_or_else(|_| "libretranslate".to_string());
+
+    match provider.to_lowercase().as_str() {
+        "libretranslate" => Some(Arc::new(LibreTranslate {
+            client,
+            url,
+            api_key,
+            timeout: TRANSLATE_TIMEOUT,
+        })),
+        "deepl" => match api_key {
+            Some(api_key) => Some(Arc::new(DeepL {
+                client,
+                url,
+                api_key,
+                timeout: TRANSLATE_TIMEOUT,
+            })),
+            None => {
+                tracing::warn!("TRANSLATOR_PROVIDER=deepl requires TRANSLATOR_API_KEY, translation disabled");
+                None
+            }
+        },
+        other => {
+            tracing::warn!("Unknown TRANSLATOR_PROVIDER '{}', translation disabled", other);
+            None
+        }
+    }
+}
+
+pub struct LibreTranslate {
+    client: Client,
+    url: String,
+    api_key: Option<String>,
+    timeout: Duration,
+}
+
+#[derive(Debug, Deserialize)]
+struct LibreTranslateResponse {
+    #[serde(rename = "translatedText")]
+    translated_text: String,
+    #[serde(rename = "detectedLanguage")]
+    detected_language: Option<LibreTranslateDetection>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LibreTranslateDetection {
+    language: String,
+}
+
+#[async_trait]
+impl Translator for LibreTranslate {
+    fn provider(&self) -> &str {
+        "LibreTranslate"
+    }
+
+    async fn translate_to_english(&self, text: &str) -> Result<Translation, anyhow::Error> {
+        let response = self
+            .client
+            .post(format!("{}/translate", self.url.trim_end_matches('/')))
+            .timeout(self.timeout)
+            .json(&serde_json::json!({
+                "q": text,
+                "source": "auto",
+                "target": "en",
+                "format": "text",
+                "api_key": self.api_key,
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("LibreTranslate returned {}", response.status()));
+        }
+
+        let body: LibreTranslateResponse = response.json().await?;
+        Ok(Translation {
+            detected_language: body
+                .detected_language
+                .map(|d| d.language)
+                .unwrap_or_else(|| "unknown".to_string()),
+            text: body.translated_text,
+        })
+    }
+}
+
+pub struct DeepL {
+    client: Client,
+    url: String,
+    api_key: String,
+    timeout: Duration,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepLResponse {
+    translations: Vec<DeepLTranslation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepLTranslation {
+    detected_source_language: String,
+    text: String,
+}
+
+#[async_trait]
+impl Translator for DeepL {
+    fn provider(&self) -> &str {
+        "DeepL"
+    }
+
+    async fn translate_to_english(&self, text: &str) -> Result<Translation, anyhow::Error> {
+        let response = self
+            .client
+            .post(format!("{}/v2/translate", self.url.trim_end_matches('/')))
+            .timeout(self.timeout)
+            .header("Authorization", format!("DeepL-Auth-Key {}", self.api_key))
+            .json(&serde_json::json!({
+                "text": [text],
+                "target_lang": "EN",
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("DeepL returned {}", response.status()));
+        }
+
+        let body: DeepLResponse = response.json().await?;
+        let translation = body
+            .translations
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("DeepL returned no translations"))?;
+
+        Ok(Translation {
+            detected_language: translation.detected_source_language.to_lowercase(),
+            text: translation.text,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use std::sync::Mutex;
+    use warp::Filter;
+
+    fn libretranslate(url: String, timeout: Duration) -> LibreTranslate {
+        LibreTranslate {
+            client: Client::new(),
+            url,
+            api_key: Some("libre-key".to_string()),
+            timeout,
+        }
+    }
+
+    #[tokio::test]
+    async fn libretranslate_request_and_response() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        let url = test_support::serve(warp::path!("translate").and(warp::post()).and(warp::body::json()).map(
+            move |body: serde_json::Value| {
+                seen.lock().unwrap().push(body);
+                warp::reply::json(&serde_json::json!({
+                    "translatedText": "Hello",
+                    "detectedLanguage": { "confidence": 90.0, "language": "de" }
+                }))
+            },
+        ));
+
+        let translation = libretranslate(format!("{}/", url), TRANSLATE_TIMEOUT)
+            .translate_to_english("Hallo")
+            .await
+            .unwrap();
+        assert_eq!(translation.detected_language, "de");
+        assert_eq!(translation.text, "Hello");
+        assert_eq!(
+            requests.lock().unwrap()[0],
+            serde_json::json!({
+                "q": "Hallo",
+                "source": "auto",
+                "target": "en",
+                "format": "text",
+                "api_key": "libre-key",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn libretranslate_without_detection_or_with_error_status() {
+        let url = test_support::serve(
+            warp::path!("ok" / "translate")
+                .map(|| warp::reply::json(&serde_json::json!({ "translatedText": "Hello" })))
+                .or(warp::path!("down" / "translate").map(|| {
+                    warp::reply::with_status("busy", warp::http::StatusCode::SERVICE_UNAVAILABLE)
+                })),
+        );
+
+        let translation = libretranslate(format!("{}/ok", url), TRANSLATE_TIMEOUT)
+            .translate_to_english("Hallo")
+            .await
+            .unwrap();
+        assert_eq!(translation.detected_language, "unknown");
+
+        let err = libretranslate(format!("{}/down", url), TRANSLATE_TIMEOUT)
+            .translate_to_english("Hallo")
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "LibreTranslate returned 503 Service Unavailable");
+    }
+
+    #[tokio::test]
+    async fn deepl_request_and_response() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        let url = test_support::serve(
+            warp::path!("v2" / "translate")
+                .and(warp::post())
+                .and(warp::header::<String>("authorization"))
+                .and(warp::body::json())
+                .map(move |authorization: String, body: serde_json::Value| {
+                    seen.lock().unwrap().push((authorization, body));
+                    warp::reply::json(&serde_json::json!({
+                        "translations": [{ "detected_source_language": "FR", "text": "Hello" }]
+                    }))
+                }),
+        );
+
+        let deepl = DeepL {
+            client: Client::new(),
+            url,
+            api_key: "deepl-key".to_string(),
+            timeout: TRANSLATE_TIMEOUT,
+        };
+        let translation = deepl.translate_to_english("Bonjour").await.unwrap();
+        assert_eq!(translation.detected_language, "fr");
+        assert_eq!(translation.text, "Hello");
+
+        let (authorization, body) = requests.lock().unwrap()[0].clone();
+        assert_eq!(authorization, "DeepL-Auth-Key deepl-key");
+        assert_eq!(body, serde_json::json!({ "text": ["Bonjour"], "target_lang": "EN" }));
+    }
+
+    #[tokio::test]
+    async fn slow_provider_times_out() {
+        let url = test_support::serve(warp::path!("translate").then(|| async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            warp::reply::json(&serde_json::json!({ "translatedText": "late" }))
+        }));
+
+        let started = std::time::Instant::now();
+        let err = libretranslate(url, Duration::from_millis(200))
+            .translate_to_english("Hallo")
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<reqwest::Error>().is_some_and(reqwest::Error::is_timeout), "{}", err);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}